@@ -1,18 +1,70 @@
 ///! Defines Python bindings for `vidyut_cheda`.
 use vidyut_cheda::{Chedaka, Config, Error};
 
-use crate::kosha::semantics::PyPada;
+use vidyut_kosha::semantics::Pada;
+
+use crate::kosha::semantics::{lemma_of, PyPada};
+use crate::kosha::Kosha;
+use crate::lipi::{to_scheme, PyScheme};
 use pyo3::exceptions::{PyOSError, PyValueError};
 use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+mod align;
+pub mod boundary;
+pub mod compound;
+pub mod conllu;
+pub mod constraints;
+mod data;
+mod document;
+pub mod evaluate;
+mod join;
+pub mod lattice;
+mod lexicon;
+pub mod stream;
+pub mod unknown;
+
+use align::{SourceMap, Span};
+use boundary::PyBoundary;
+use constraints::{Edit, Ends, Piece, PyConstraints};
+use data::Data;
+use lattice::PyLattice;
+use stream::PyFileIter;
+use unknown::PyGuess;
+
+pyo3::create_exception!(
+    cheda,
+    UnknownWordError,
+    PyValueError,
+    "Raised in strict mode if some part of the input could not be analyzed.\n\n\
+     The exception's arguments are a message and a list of `(start, end, surface)` tuples, one \
+     per unknown token."
+);
 
 /// A token.
 #[pyclass(name = "Token", get_all)]
+#[derive(Clone)]
 pub struct PyToken {
     /// The token text.
     pub text: String,
     /// Other information associated with the token.
     pub info: PyPada,
+    /// The char offset in the input where this token begins.
+    pub start: usize,
+    /// The char offset in the input where this token ends.
+    pub end: usize,
+    /// The part of the input that this token covers.
+    ///
+    /// Due to sandhi, this often differs from `text`.
+    pub surface: String,
+    /// Whether this token is missing from all lexicons. If so, `info` is `Pada(None)`.
+    pub is_unknown: bool,
+    /// If this token is unknown, a heuristic guess at its stem and ending.
+    pub guess: Option<PyGuess>,
 }
 
 #[pymethods]
@@ -32,43 +84,777 @@ impl PyToken {
     }
 }
 
+/// A sentence or half-verse in a document.
+#[pyclass(name = "Sentence", get_all)]
+pub struct PySentence {
+    /// The text of this sentence.
+    pub text: String,
+    /// The char offset in the document where this sentence begins.
+    pub start: usize,
+    /// The char offset in the document where this sentence ends.
+    pub end: usize,
+    /// Whether this sentence closes a verse (`॥` or `||`).
+    pub ends_verse: bool,
+    /// The number of the verse that this sentence closes, if any.
+    pub verse_number: Option<String>,
+    /// The tokens in this sentence. Their offsets refer to the document.
+    pub tokens: Vec<PyToken>,
+}
+
+#[pymethods]
+impl PySentence {
+    fn __repr__(&self) -> String {
+        format!(
+            "Sentence<(text='{}', start={}, end={}, tokens={})>",
+            self.text,
+            self.start,
+            self.end,
+            self.tokens.len()
+        )
+    }
+}
+
 /// A Sanskrit segmentation engine.
-#[pyclass(name = "Chedaka")]
+///
+/// A `Chedaka` can be pickled, e.g. to send it to `multiprocessing` workers. Pickling stores only
+/// the paths it was loaded from, so these files must still exist when it is unpickled.
+///
+/// Some features, such as `lattice`, `join`, and `constraints` in `run`, read more data from the
+/// data directory on first use. So, the data directory should exist for as long as the `Chedaka`
+/// does.
+#[pyclass(name = "Chedaka", module = "vidyut.cheda")]
 pub struct PyChedaka {
     chedaka: Chedaka,
+    /// The data directory that `chedaka` was loaded from.
+    path: PathBuf,
+    /// Extra data from `path` that `chedaka` does not expose, which we load on first use.
+    ///
+    /// `chedaka` does not share its copy of the lexicon, so loading this data doubles the memory
+    /// that the lexicon uses. We load it lazily so that callers who never need it don't pay.
+    data: OnceLock<Data>,
+    /// User lexicons to consult alongside the lexicon in `chedaka`.
+    lexicons: Vec<Arc<vidyut_kosha::Kosha>>,
+    /// The `Kosha` objects that `lexicons` came from, which we need for pickling.
+    extra_lexicons: Vec<Kosha>,
 }
 
 #[pymethods]
 impl PyChedaka {
     /// Initialize `Chedaka` by reading the necessary data from the directory at `path`.
     ///
+    /// `extra_lexicons` is a list of `Kosha` objects to consult alongside the bundled lexicon.
+    /// For details, see `add_lexicon`.
+    ///
     /// This constructor raises a ValueError if the initialiation fails.
     #[new]
-    fn new(path: PathBuf) -> PyResult<Self> {
-        let config = Config::new(path);
+    #[pyo3(signature = (path, *, extra_lexicons = Vec::new()))]
+    fn new(path: PathBuf, extra_lexicons: Vec<PyRef<Kosha>>) -> PyResult<Self> {
+        let config = Config::new(path.clone());
         match Chedaka::new(config) {
-            Ok(chedaka) => Ok(PyChedaka { chedaka }),
+            Ok(chedaka) => Ok(PyChedaka {
+                chedaka,
+                path,
+                data: OnceLock::new(),
+                lexicons: extra_lexicons.iter().map(|k| k.shared()).collect(),
+                extra_lexicons: extra_lexicons.iter().map(|k| Kosha::clone(k)).collect(),
+            }),
             Err(e) => Err(WrappedError(e).into()),
         }
     }
 
-    /// Parse the given SLP1 input and return a list of `Token` objects.
-    pub fn run(&self, slp1_text: &str) -> PyResult<Vec<PyToken>> {
-        let tokens = match self.chedaka.run(slp1_text) {
+    /// Consult `kosha` alongside the bundled lexicon in all future calls.
+    ///
+    /// Extra lexicons fill the gaps in the bundled lexicon: wherever `Chedaka` cannot analyze
+    /// part of the input, a key from an extra lexicon in that part becomes a single token whose
+    /// `info` is the key's first entry. If several keys overlap, the leftmost and then the longest
+    /// one wins. Words that `Chedaka` can already analyze are never split. Keys match the input
+    /// as-is, so a word whose sounds changed due to sandhi will not match.
+    pub fn add_lexicon(&mut self, kosha: &Kosha) {
+        self.lexicons.push(kosha.shared());
+        self.extra_lexicons.push(kosha.clone());
+    }
+
+    /// Return the arguments that recreate this `Chedaka` when it is unpickled.
+    ///
+    /// We pickle a `Chedaka` by its data path and the paths of its extra lexicons, so unpickling
+    /// loads the data again from disk. To pay the loading cost only once, create the `Chedaka`
+    /// before starting a `multiprocessing` pool that uses the `fork` start method: forked workers
+    /// then share the parent's memory until they write to it.
+    fn __getnewargs_ex__(&self) -> ((PathBuf,), HashMap<&'static str, Vec<Kosha>>) {
+        let kwargs = HashMap::from([("extra_lexicons", self.extra_lexicons.clone())]);
+        ((self.path.clone(),), kwargs)
+    }
+
+    /// Parse the given input and return a list of `Token` objects.
+    ///
+    /// `input_scheme` is the scheme that `text` uses, and `output_scheme` is the scheme to use
+    /// for each token's `text`. Token offsets and `surface` always refer to the original `text`.
+    /// Token `info` always uses SLP1.
+    ///
+    /// If `constraints` is set, the output is the best segmentation consistent with them. If
+    /// sandhi joins the words on either side of a forced boundary, as in `gacCaty|arjunas`, we
+    /// first undo that sandhi with this `Chedaka`'s sandhi rules, then parse the text between
+    /// forced boundaries independently. So, the model does not score transitions across a forced
+    /// boundary. A span forced to be one token takes its `info` from parsing the span on its own
+    /// if that yields exactly one token, and is unknown otherwise.
+    ///
+    /// Tokens missing from all lexicons have `is_unknown` set. If `strict` is true, this method
+    /// instead raises an `UnknownWordError` that lists every unknown token.
+    #[pyo3(signature = (
+        text,
+        *,
+        input_scheme = PyScheme::Slp1,
+        output_scheme = PyScheme::Slp1,
+        constraints = None,
+        strict = false,
+    ))]
+    pub fn run(
+        &self,
+        text: &str,
+        input_scheme: PyScheme,
+        output_scheme: PyScheme,
+        constraints: Option<PyConstraints>,
+        strict: bool,
+    ) -> PyResult<Vec<PyToken>> {
+        let tokens = self.run_inner(text, input_scheme, output_scheme, constraints)?;
+        if strict {
+            check_known(&tokens)?;
+        }
+        Ok(tokens)
+    }
+
+    /// Split the given document into sentences and half-verses, then parse each one.
+    ///
+    /// Units end at a *daṇḍa* (`।` or `|`), a double *daṇḍa* (`॥` or `||`), or a blank line. A
+    /// verse number after a double *daṇḍa*, as in `॥ १ ॥`, is stored on the `Sentence` it closes
+    /// and is not parsed. For details on `input_scheme`, `output_scheme`, and `strict`, see
+    /// `run`.
+    #[pyo3(signature = (
+        text,
+        *,
+        input_scheme = PyScheme::Slp1,
+        output_scheme = PyScheme::Slp1,
+        strict = false,
+    ))]
+    pub fn run_document(
+        &self,
+        text: &str,
+        input_scheme: PyScheme,
+        output_scheme: PyScheme,
+        strict: bool,
+    ) -> PyResult<Vec<PySentence>> {
+        let chars: Vec<char> = text.chars().collect();
+
+        let mut ret = Vec::new();
+        for unit in document::split_units(text) {
+            let (start, end) = unit.span;
+            let unit_text: String = chars[start..end].iter().collect();
+
+            let mut tokens = match self.segment(&unit_text, input_scheme, output_scheme) {
+                Ok(tokens) => tokens,
+                Err(e) => return Err(WrappedError(e).into()),
+            };
+            shift(&mut tokens, start);
+
+            ret.push(PySentence {
+                text: unit_text,
+                start,
+                end,
+                ends_verse: unit.ends_verse,
+                verse_number: unit.verse_number,
+                tokens,
+            });
+        }
+
+        if strict {
+            let tokens: Vec<_> = ret.iter().flat_map(|s| s.tokens.iter().cloned()).collect();
+            check_known(&tokens)?;
+        }
+        Ok(ret)
+    }
+
+    /// Split the given document into sentences, parse each one, and return the result as
+    /// CoNLL-U.
+    ///
+    /// Sentences are split as in `run_document`. Each sentence has `sent_id` and `text` comments.
+    /// For details on the token fields, see `vidyut.cheda.to_conllu`.
+    #[pyo3(signature = (text, *, input_scheme = PyScheme::Slp1, output_scheme = PyScheme::Slp1))]
+    pub fn run_conllu(
+        &self,
+        text: &str,
+        input_scheme: PyScheme,
+        output_scheme: PyScheme,
+    ) -> PyResult<String> {
+        let sentences = self.run_document(text, input_scheme, output_scheme, false)?;
+
+        let mut ret = String::new();
+        for (i, sentence) in sentences.iter().enumerate() {
+            let sent_id = (i + 1).to_string();
+            let comments = [
+                ("sent_id", sent_id.as_str()),
+                ("text", sentence.text.as_str()),
+            ];
+            conllu::write_sentence(&mut ret, &comments, &sentence.tokens);
+        }
+        Ok(ret)
+    }
+
+    /// Return an iterator that segments the file at `path` one line at a time.
+    ///
+    /// The iterator yields a `(line_number, tokens)` pair for each non-blank line, where
+    /// `line_number` is 1-based. If `by_verse` is true, it instead yields one pair per verse,
+    /// where a verse is a run of lines that ends with a double *daṇḍa* (`॥` or `||`) or a blank
+    /// line, and `line_number` is the verse's first line. Token offsets refer to the line or
+    /// verse, whose lines are joined with `\n`. As in `run_document`, *daṇḍa*s and verse
+    /// numbers are not parsed.
+    ///
+    /// The file is read incrementally, so memory use does not grow with the size of the file.
+    /// `encoding` is the file's encoding and accepts any name that Python's `open` accepts.
+    /// `scheme` is the scheme of the file and of the output tokens.
+    #[pyo3(signature = (path, *, encoding = "utf-8", scheme = PyScheme::Slp1, by_verse = false))]
+    pub fn iter_file(
+        slf: PyRef<'_, Self>,
+        path: PathBuf,
+        encoding: &str,
+        scheme: PyScheme,
+        by_verse: bool,
+    ) -> PyResult<PyFileIter> {
+        let py = slf.py();
+        PyFileIter::new(py, slf.into(), &path, encoding, scheme, by_verse)
+    }
+
+    /// Return every candidate word that `Chedaka` considers for the given SLP1 input.
+    ///
+    /// Each node in the returned `Lattice` is a candidate word with its span and analysis, and
+    /// each edge is a sandhi transition between two candidates. Nodes that `run` chose have
+    /// `in_best_path` set. The lattice does not include model scores, since `vidyut_cheda` does
+    /// not expose them.
+    pub fn lattice(&self, slp1_text: &str) -> PyResult<PyLattice> {
+        let best = match self.segment_slp1(slp1_text) {
+            Ok(best) => best,
+            Err(e) => return Err(WrappedError(e).into()),
+        };
+        Ok(lattice::build(
+            self.data()?,
+            &self.lexicons,
+            slp1_text,
+            best,
+        ))
+    }
+
+    /// Parse the given SLP1 input and explain how each pair of adjacent tokens was joined.
+    ///
+    /// Each `Boundary` in the returned list covers two adjacent tokens from `run`. It gives the
+    /// part of the input they cover, their underlying forms, and the rule from the data
+    /// directory's sandhi rules that joins them. For example, for `rAmo gacCati`, the boundary
+    /// between `rAmas` and `gacCati` has the rule `as` + `g` = `o g`.
+    pub fn sandhi_boundaries(&self, slp1_text: &str) -> PyResult<Vec<PyBoundary>> {
+        let tokens = match self.segment_slp1(slp1_text) {
             Ok(tokens) => tokens,
             Err(e) => return Err(WrappedError(e).into()),
         };
+        Ok(boundary::explain(self.data()?, slp1_text, &tokens))
+    }
+
+    /// Join the given words with sandhi and return the resulting text.
+    ///
+    /// `words` is a list of `Token` objects or strings, such as the output of `run` after some
+    /// edits. Between each pair of adjacent words, we apply the most specific matching rule from
+    /// this `Chedaka`'s sandhi rules. If no rule matches, we separate the words with a space.
+    ///
+    /// `scheme` is the scheme of the words, including the text of each `Token`, and of the
+    /// result. So if the tokens came from `run` with some `output_scheme`, pass the same scheme
+    /// here.
+    ///
+    /// If `return_all` is true, return a list of every way to join the words instead, starting
+    /// with the default result. This list can grow quickly with the number of words.
+    #[pyo3(signature = (words, *, scheme = PyScheme::Slp1, return_all = false))]
+    pub fn join(
+        &self,
+        py: Python,
+        words: Vec<join::Word>,
+        scheme: PyScheme,
+        return_all: bool,
+    ) -> PyResult<PyObject> {
+        let words: Vec<String> = words
+            .into_iter()
+            .map(|w| to_scheme(&w.into_text(), scheme, PyScheme::Slp1))
+            .collect();
+        let rules = &self.data()?.rules;
+        if return_all {
+            let ret: Vec<String> = join::join_all(rules, &words)
+                .iter()
+                .map(|x| to_scheme(x, PyScheme::Slp1, scheme))
+                .collect();
+            Ok(ret.into_py(py))
+        } else {
+            let ret = to_scheme(&join::join(rules, &words), PyScheme::Slp1, scheme);
+            Ok(ret.into_py(py))
+        }
+    }
+
+    /// Parse each string in `texts` in parallel and return one result per input.
+    ///
+    /// Each result is either a list of `Token` objects or, if `run` would have raised an
+    /// exception for that input, the exception itself. A failure on one input does not affect
+    /// the rest of the batch.
+    ///
+    /// `num_threads` sets the number of worker threads. If it is `None`, use a shared pool with
+    /// one thread per CPU. This method releases the GIL while it segments. For details on
+    /// `input_scheme`, `output_scheme`, and `strict`, see `run`.
+    #[pyo3(signature = (
+        texts,
+        num_threads = None,
+        *,
+        input_scheme = PyScheme::Slp1,
+        output_scheme = PyScheme::Slp1,
+        strict = false,
+    ))]
+    pub fn run_batch(
+        &self,
+        py: Python,
+        texts: Vec<String>,
+        num_threads: Option<usize>,
+        input_scheme: PyScheme,
+        output_scheme: PyScheme,
+        strict: bool,
+    ) -> PyResult<Vec<PyObject>> {
+        let results = self.par_map(py, &texts, num_threads, |text| {
+            self.segment(text, input_scheme, output_scheme)
+        })?;
+
+        Ok(results
+            .into_iter()
+            .map(|result| {
+                let result = match result {
+                    Ok(tokens) if strict => check_known(&tokens).map(|_| tokens),
+                    Ok(tokens) => Ok(tokens),
+                    Err(e) => Err(WrappedError(e).into()),
+                };
+                match result {
+                    Ok(tokens) => tokens.into_py(py),
+                    Err(e) => e.value(py).into_py(py),
+                }
+            })
+            .collect())
+    }
+
+    /// Return the lemma of each token in the given input.
+    ///
+    /// This method is faster than calling `run` and reading each token's `lemma`, since it
+    /// doesn't create `Token` or `Pada` objects. Tokens that are missing from all lexicons have
+    /// a lemma of `None`. Lemmas always use SLP1. For details on `input_scheme`, see `run`.
+    #[pyo3(signature = (text, *, input_scheme = PyScheme::Slp1))]
+    pub fn lemmatize(&self, text: &str, input_scheme: PyScheme) -> PyResult<Vec<Option<String>>> {
+        match self.lemmatize_inner(text, input_scheme) {
+            Ok(lemmas) => Ok(lemmas),
+            Err(e) => Err(WrappedError(e).into()),
+        }
+    }
+
+    /// Lemmatize each string in `texts` in parallel and return one result per input.
+    ///
+    /// Each result is either a list of lemmas or, if `lemmatize` would have raised an exception
+    /// for that input, the exception itself. For details on `num_threads`, see `run_batch`.
+    #[pyo3(signature = (texts, num_threads = None, *, input_scheme = PyScheme::Slp1))]
+    pub fn lemmatize_batch(
+        &self,
+        py: Python,
+        texts: Vec<String>,
+        num_threads: Option<usize>,
+        input_scheme: PyScheme,
+    ) -> PyResult<Vec<PyObject>> {
+        let results = self.par_map(py, &texts, num_threads, |text| {
+            self.lemmatize_inner(text, input_scheme)
+        })?;
+
+        Ok(results
+            .into_iter()
+            .map(|result| match result {
+                Ok(lemmas) => lemmas.into_py(py),
+                Err(e) => PyErr::from(WrappedError(e)).value(py).into_py(py),
+            })
+            .collect())
+    }
+}
+
+/// Raises an `UnknownWordError` if any of `tokens` is unknown.
+fn check_known(tokens: &[PyToken]) -> PyResult<()> {
+    let spans: Vec<_> = tokens
+        .iter()
+        .filter(|t| t.is_unknown)
+        .map(|t| (t.start, t.end, t.surface.clone()))
+        .collect();
+    if spans.is_empty() {
+        return Ok(());
+    }
+
+    let surfaces: Vec<_> = spans.iter().map(|(_, _, s)| s.as_str()).collect();
+    let message = format!("Could not analyze: {}", surfaces.join(", "));
+    Err(UnknownWordError::new_err((message, spans)))
+}
+
+/// Moves `tokens` right by `offset` chars.
+fn shift(tokens: &mut [PyToken], offset: usize) {
+    for token in tokens.iter_mut() {
+        token.start += offset;
+        token.end += offset;
+    }
+}
+
+impl PyChedaka {
+    /// Returns the extra data for this `Chedaka`, loading it if necessary.
+    fn data(&self) -> PyResult<&Data> {
+        if let Some(data) = self.data.get() {
+            return Ok(data);
+        }
+        let data = Data::new(&self.path)?;
+        Ok(self.data.get_or_init(|| data))
+    }
+
+    /// Applies `f` to each of `texts` in parallel without holding the GIL.
+    ///
+    /// If `num_threads` is `None`, we use rayon's global pool. Otherwise, we build a pool with
+    /// `num_threads` threads for this call.
+    fn par_map<T, F>(
+        &self,
+        py: Python,
+        texts: &[String],
+        num_threads: Option<usize>,
+        f: F,
+    ) -> PyResult<Vec<Result<T, Error>>>
+    where
+        T: Send,
+        F: Fn(&str) -> Result<T, Error> + Sync,
+    {
+        let run = || texts.par_iter().map(|text| f(text)).collect::<Vec<_>>();
+        let num_threads = match num_threads {
+            Some(n) => n,
+            None => return Ok(py.allow_threads(run)),
+        };
+
+        let results = py.allow_threads(|| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()?;
+            Ok::<_, rayon::ThreadPoolBuildError>(pool.install(run))
+        });
+
+        match results {
+            Ok(results) => Ok(results),
+            Err(e) => Err(PyOSError::new_err(format!("{}", e))),
+        }
+    }
+
+    /// Returns the lemma of each token in `text`, which uses `input_scheme`.
+    fn lemmatize_inner(
+        &self,
+        text: &str,
+        input_scheme: PyScheme,
+    ) -> Result<Vec<Option<String>>, Error> {
+        let slp1_text = to_scheme(text, input_scheme, PyScheme::Slp1);
+        if self.lexicons.is_empty() {
+            // Lemmas don't need spans, so skip the alignment in `segment_slp1`.
+            return Ok(self
+                .chedaka
+                .run(&slp1_text)?
+                .iter()
+                .map(|t| lemma_of(t.info()).map(|x| x.to_string()))
+                .collect());
+        }
+        Ok(self
+            .segment_slp1(&slp1_text)?
+            .iter()
+            .map(|(_, info, _)| lemma_of(info).map(|x| x.to_string()))
+            .collect())
+    }
+
+    /// Runs `run` without the `strict` check.
+    fn run_inner(
+        &self,
+        text: &str,
+        input_scheme: PyScheme,
+        output_scheme: PyScheme,
+        constraints: Option<PyConstraints>,
+    ) -> PyResult<Vec<PyToken>> {
+        let constraints = match constraints {
+            Some(c) => c,
+            None => {
+                return match self.segment(text, input_scheme, output_scheme) {
+                    Ok(tokens) => Ok(tokens),
+                    Err(e) => Err(WrappedError(e).into()),
+                }
+            }
+        };
+
+        let chars: Vec<char> = text.chars().collect();
+        let pieces = constraints.pieces(chars.len())?;
+        let texts: Vec<String> = pieces
+            .iter()
+            .map(|p| {
+                let (start, end) = p.span();
+                chars[start..end].iter().collect()
+            })
+            .collect();
+        let all_ends = self.undo_boundary_sandhi(&texts, input_scheme)?;
 
         let mut ret = Vec::new();
-        for token in tokens {
-            ret.push(PyToken {
-                text: token.text().to_string(),
-                info: token.info().clone().into(),
+        for ((piece, piece_text), ends) in pieces.iter().zip(texts).zip(all_ends) {
+            let (start, end) = piece.span();
+            let result = self.segment_with(&piece_text, input_scheme, output_scheme, |slp1| {
+                let underlying = match ends.apply(slp1) {
+                    Some(u) => u,
+                    None => return self.segment_slp1(slp1),
+                };
+                let len = slp1.chars().count();
+                Ok(self
+                    .segment_slp1(&underlying)?
+                    .into_iter()
+                    .map(|(text, info, span)| (text, info, ends.to_surface(span, len)))
+                    .collect())
             });
+            let mut tokens = match result {
+                Ok(tokens) => tokens,
+                Err(e) => return Err(WrappedError(e).into()),
+            };
+
+            if let Piece::Token(_) = piece {
+                let token = match tokens.len() {
+                    1 => tokens.remove(0),
+                    _ => {
+                        let slp1_text = to_scheme(&piece_text, input_scheme, PyScheme::Slp1);
+                        let slp1_text = ends.apply(&slp1_text).unwrap_or(slp1_text);
+                        PyToken {
+                            text: to_scheme(&slp1_text, PyScheme::Slp1, output_scheme),
+                            info: PyPada::default(),
+                            start: 0,
+                            end: 0,
+                            surface: String::new(),
+                            is_unknown: true,
+                            guess: unknown::guess(&slp1_text),
+                        }
+                    }
+                };
+                tokens = vec![PyToken {
+                    start: 0,
+                    end: end - start,
+                    surface: piece_text,
+                    ..token
+                }];
+            }
+
+            shift(&mut tokens, start);
+            ret.extend(tokens);
         }
+        Ok(ret)
+    }
 
+    /// Segments `text`, which uses `input_scheme`, and returns tokens whose text uses
+    /// `output_scheme`.
+    fn segment(
+        &self,
+        text: &str,
+        input_scheme: PyScheme,
+        output_scheme: PyScheme,
+    ) -> Result<Vec<PyToken>, Error> {
+        self.segment_with(text, input_scheme, output_scheme, |slp1_text| {
+            self.segment_slp1(slp1_text)
+        })
+    }
+
+    /// Like `segment`, but uses `f` to segment the SLP1 version of `text`.
+    fn segment_with(
+        &self,
+        text: &str,
+        input_scheme: PyScheme,
+        output_scheme: PyScheme,
+        f: impl FnOnce(&str) -> Result<Vec<(String, Pada, Span)>, Error>,
+    ) -> Result<Vec<PyToken>, Error> {
+        let (slp1_text, source_map) = if input_scheme == PyScheme::Slp1 {
+            (Cow::Borrowed(text), None)
+        } else {
+            let (slp1_text, source_map) =
+                SourceMap::new(text, |x| to_scheme(x, input_scheme, PyScheme::Slp1));
+            (Cow::Owned(slp1_text), Some(source_map))
+        };
+
+        let chars: Vec<char> = text.chars().collect();
+        Ok(f(&slp1_text)?
+            .into_iter()
+            .map(|(token_text, info, span)| {
+                let (start, end) = match &source_map {
+                    Some(m) => m.to_source(span),
+                    None => span,
+                };
+                let info: PyPada = info.into();
+                let is_unknown = info.is_unknown();
+                PyToken {
+                    text: to_scheme(&token_text, PyScheme::Slp1, output_scheme),
+                    guess: if is_unknown {
+                        unknown::guess(&token_text)
+                    } else {
+                        None
+                    },
+                    info,
+                    start,
+                    end,
+                    surface: chars[start..end].iter().collect(),
+                    is_unknown,
+                }
+            })
+            .collect())
+    }
+
+    /// Returns how to undo the sandhi at each boundary between `pieces`, which use `scheme`.
+    ///
+    /// At each boundary, we try every way to split the text there that the sandhi rules allow,
+    /// and keep the one that leaves the fewest unknown tokens in the two pieces next to it. On a
+    /// tie, we keep the pieces as they are.
+    fn undo_boundary_sandhi(&self, pieces: &[String], scheme: PyScheme) -> PyResult<Vec<Ends>> {
+        let mut ret = vec![Ends::default(); pieces.len()];
+        if pieces.len() < 2 {
+            return Ok(ret);
+        }
+
+        let splitter = &self.data()?.splitter;
+        let slp1: Vec<String> = pieces
+            .iter()
+            .map(|p| to_scheme(p, scheme, PyScheme::Slp1))
+            .collect();
+        for i in 1..pieces.len() {
+            let junctions = constraints::junctions(splitter, &slp1[i - 1], &slp1[i]);
+            if junctions.len() < 2 {
+                continue;
+            }
+
+            let mut best: Option<(usize, Edit, Edit)> = None;
+            for (tail, head) in junctions {
+                let left = Ends {
+                    tail,
+                    ..ret[i - 1].clone()
+                };
+                let right = Ends {
+                    head,
+                    ..Ends::default()
+                };
+                let (l, r) = match (left.apply(&slp1[i - 1]), right.apply(&slp1[i])) {
+                    (Some(l), Some(r)) => (l, r),
+                    _ => continue,
+                };
+                let cost = self.num_unknown(&l).saturating_add(self.num_unknown(&r));
+                let is_better = match &best {
+                    Some((best_cost, _, _)) => cost < *best_cost,
+                    None => true,
+                };
+                if is_better {
+                    best = Some((cost, left.tail, right.head));
+                }
+            }
+            if let Some((_, tail, head)) = best {
+                ret[i - 1].tail = tail;
+                ret[i].head = head;
+            }
+        }
         Ok(ret)
     }
+
+    /// Returns the number of unknown tokens in the SLP1 string `text`.
+    fn num_unknown(&self, text: &str) -> usize {
+        match self.segment_slp1(text) {
+            Ok(tokens) => tokens
+                .iter()
+                .filter(|(_, info, _)| matches!(info, Pada::None))
+                .count(),
+            Err(_) => usize::MAX,
+        }
+    }
+
+    /// Segments the SLP1 string `text` and returns the text, info, and span of each token.
+    ///
+    /// We run the bundled model first and consult the user lexicons only for the tokens that the
+    /// model could not analyze. So, a user lexicon never splits a word that the model knows.
+    fn segment_slp1(&self, text: &str) -> Result<Vec<(String, Pada, Span)>, Error> {
+        let chars: Vec<char> = text.chars().collect();
+        let tokens = self.run_span(&chars, (0, chars.len()))?;
+        if self.lexicons.is_empty() {
+            return Ok(tokens);
+        }
+
+        let is_unknown = |i: usize| matches!(tokens[i].1, Pada::None);
+        let mut ret = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            if !is_unknown(i) {
+                ret.push(tokens[i].clone());
+                i += 1;
+                continue;
+            }
+
+            let mut j = i;
+            while j < tokens.len() && is_unknown(j) {
+                j += 1;
+            }
+            ret.extend(self.run_unknown(&chars, &tokens[i..j])?);
+            i = j;
+        }
+        Ok(ret)
+    }
+
+    /// Segments the span that `unknown` covers again with the help of the user lexicons.
+    ///
+    /// `unknown` is a run of adjacent tokens that the bundled model could not analyze. If no key
+    /// from the user lexicons occurs in their span, we return them as-is.
+    fn run_unknown(
+        &self,
+        chars: &[char],
+        unknown: &[(String, Pada, Span)],
+    ) -> Result<Vec<(String, Pada, Span)>, Error> {
+        let start = unknown.first().map_or(0, |(_, _, span)| span.0);
+        let end = unknown.last().map_or(0, |(_, _, span)| span.1);
+        let text: String = chars[start..end].iter().collect();
+        let matches = lexicon::find_matches(&text, &self.lexicons);
+        if matches.is_empty() {
+            return Ok(unknown.to_vec());
+        }
+
+        let mut ret = Vec::new();
+        let mut prev = start;
+        for m in matches {
+            let (m_start, m_end) = (start + m.span.0, start + m.span.1);
+            if m_start > prev {
+                ret.extend(self.run_span(chars, (prev, m_start))?);
+            }
+            ret.push((m.text, m.info, (m_start, m_end)));
+            prev = m_end;
+        }
+        if end > prev {
+            ret.extend(self.run_span(chars, (prev, end))?);
+        }
+        Ok(ret)
+    }
+
+    /// Runs the bundled model on `chars[start..end]`.
+    fn run_span(
+        &self,
+        chars: &[char],
+        (start, end): Span,
+    ) -> Result<Vec<(String, Pada, Span)>, Error> {
+        let text: String = chars[start..end].iter().collect();
+        let tokens = self.chedaka.run(&text)?;
+        let spans = align::align(&text, tokens.iter().map(|t| t.text()));
+
+        Ok(tokens
+            .into_iter()
+            .zip(spans)
+            .map(|(t, (s, e))| {
+                (
+                    t.text().to_string(),
+                    t.info().clone(),
+                    (start + s, start + e),
+                )
+            })
+            .collect())
+    }
 }
 
 struct WrappedError(Error);
@@ -0,0 +1,217 @@
+//! Aligns `vidyut_cheda` tokens with the text they were parsed from.
+//!
+//! `vidyut_cheda` returns each token in its underlying form (`rAmaH`) rather than in the form it
+//! has in the input (`rAmo`). To recover each token's span, we align the input with the
+//! concatenated token texts by edit distance. Sandhi changes only a few sounds at each word
+//! boundary, so we need to search only a narrow band around the diagonal.
+
+/// A half-open range of char offsets into some input string.
+pub(crate) type Span = (usize, usize);
+
+/// How far the alignment may drift from the diagonal, in chars.
+const BAND_RADIUS: usize = 32;
+
+const DIAGONAL: u8 = 0;
+const SKIP_INPUT: u8 = 1;
+const SKIP_TOKEN: u8 = 2;
+
+/// A row of the alignment matrix that stores only the cells inside the band.
+struct Row {
+    lo: usize,
+    costs: Vec<u32>,
+    moves: Vec<u8>,
+}
+
+impl Row {
+    fn cost(&self, i: usize) -> u32 {
+        if i < self.lo {
+            return u32::MAX;
+        }
+        self.costs.get(i - self.lo).copied().unwrap_or(u32::MAX)
+    }
+}
+
+/// Returns the span of `input` that each of `tokens` covers.
+///
+/// Spans are in char offsets, appear in order, and never overlap. Whitespace that separates two
+/// tokens is not included in either span.
+pub(crate) fn align<'a>(input: &str, tokens: impl IntoIterator<Item = &'a str>) -> Vec<Span> {
+    let chars: Vec<char> = input.chars().collect();
+
+    // Whitespace never appears in a token, so drop it before aligning and remember where each of
+    // the remaining chars came from.
+    let (offsets, compact): (Vec<usize>, Vec<char>) = chars
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !c.is_whitespace())
+        .map(|(i, c)| (i, *c))
+        .unzip();
+
+    let mut target = Vec::new();
+    let mut bounds = Vec::new();
+    for token in tokens {
+        bounds.push(target.len());
+        target.extend(token.chars());
+    }
+
+    let starts = align_chars(&compact, &target, &bounds);
+
+    let n = compact.len();
+    let to_original = |i: usize| offsets.get(i).copied().unwrap_or(chars.len());
+    (0..starts.len())
+        .map(|k| {
+            let start = starts[k];
+            let end = starts.get(k + 1).copied().unwrap_or(n);
+            if end > start {
+                (to_original(start), to_original(end - 1) + 1)
+            } else {
+                (to_original(start), to_original(start))
+            }
+        })
+        .collect()
+}
+
+/// Returns, for each offset in `bounds`, the position in `input` where that offset of `target`
+/// begins.
+fn align_chars(input: &[char], target: &[char], bounds: &[usize]) -> Vec<usize> {
+    let n = input.len();
+    let m = target.len();
+    let radius = BAND_RADIUS + n / m.max(1);
+
+    let mut rows: Vec<Row> = Vec::with_capacity(m + 1);
+    for j in 0..=m {
+        let center = (j * n).checked_div(m).unwrap_or(n);
+        let lo = center.saturating_sub(radius);
+        let hi = usize::min(n, center + radius);
+
+        let mut row = Row {
+            lo,
+            costs: Vec::with_capacity(hi + 1 - lo),
+            moves: Vec::with_capacity(hi + 1 - lo),
+        };
+        for i in lo..=hi {
+            let (cost, mv) = if i == 0 && j == 0 {
+                (0, DIAGONAL)
+            } else {
+                let mut best = (u32::MAX, DIAGONAL);
+                if i > 0 && j > 0 {
+                    let prev = rows[j - 1].cost(i - 1);
+                    let step = u32::from(input[i - 1] != target[j - 1]);
+                    best = (prev.saturating_add(step), DIAGONAL);
+                }
+                if i > 0 {
+                    let prev = row.cost(i - 1).saturating_add(1);
+                    if prev < best.0 {
+                        best = (prev, SKIP_INPUT);
+                    }
+                }
+                if j > 0 {
+                    // On a tie with a substitution, prefer to skip a token char so that a sound
+                    // shared by two tokens is assigned to the first one.
+                    let prev = rows[j - 1].cost(i).saturating_add(1);
+                    let is_substitution =
+                        i > 0 && best.1 == DIAGONAL && input[i - 1] != target[j - 1];
+                    if prev < best.0 || (prev == best.0 && is_substitution) {
+                        best = (prev, SKIP_TOKEN);
+                    }
+                }
+                best
+            };
+            row.costs.push(cost);
+            row.moves.push(mv);
+        }
+        rows.push(row);
+    }
+
+    // Walk back from the end and record, for each row, the leftmost input position on the path.
+    let mut first_i = vec![n; m + 1];
+    let (mut i, mut j) = (n, m);
+    loop {
+        first_i[j] = i;
+        if i == 0 && j == 0 {
+            break;
+        }
+        let row = &rows[j];
+        match row.moves[i - row.lo] {
+            DIAGONAL if i > 0 && j > 0 => {
+                i -= 1;
+                j -= 1;
+            }
+            SKIP_INPUT if i > 0 => i -= 1,
+            _ if j > 0 => j -= 1,
+            _ => i -= 1,
+        }
+    }
+
+    bounds.iter().map(|b| first_i[*b]).collect()
+}
+
+/// Maps char offsets in a transliterated string back to char offsets in its source string.
+pub(crate) struct SourceMap {
+    /// Pairs of (output offset, source offset) at which the transliteration can be split cleanly.
+    points: Vec<(usize, usize)>,
+}
+
+impl SourceMap {
+    /// Transliterates `source` with `f` and returns the result along with its source map.
+    ///
+    /// We transliterate each word separately and keep whitespace as-is. Within a word, offset
+    /// `k` is a sync point if transliterating the two halves of the word separately gives the
+    /// same result as transliterating the whole word. This is quadratic in word length, but
+    /// words are short.
+    pub(crate) fn new(source: &str, f: impl Fn(&str) -> String) -> (String, Self) {
+        let chars: Vec<char> = source.chars().collect();
+        let mut output = String::new();
+        let mut out_len = 0;
+        let mut points = vec![(0, 0)];
+
+        let mut i = 0;
+        while i < chars.len() {
+            let is_space = chars[i].is_whitespace();
+            let mut j = i;
+            while j < chars.len() && chars[j].is_whitespace() == is_space {
+                j += 1;
+            }
+
+            let run = &chars[i..j];
+            if is_space {
+                for (k, c) in run.iter().enumerate() {
+                    output.push(*c);
+                    points.push((out_len + k + 1, i + k + 1));
+                }
+                out_len += run.len();
+            } else {
+                let word: String = run.iter().collect();
+                let full = f(&word);
+                for k in 1..run.len() {
+                    let head = f(&run[..k].iter().collect::<String>());
+                    let tail = f(&run[k..].iter().collect::<String>());
+                    if full.strip_prefix(head.as_str()) == Some(tail.as_str()) {
+                        points.push((out_len + head.chars().count(), i + k));
+                    }
+                }
+                out_len += full.chars().count();
+                points.push((out_len, j));
+                output.push_str(&full);
+            }
+            i = j;
+        }
+
+        (output, Self { points })
+    }
+
+    /// Maps `span`, which uses offsets into the output string, to offsets into the source string.
+    pub(crate) fn to_source(&self, span: Span) -> Span {
+        let (start, end) = span;
+        let i = self.points.partition_point(|(out, _)| *out <= start);
+        let start = self.points[i.saturating_sub(1)].1;
+
+        let i = self.points.partition_point(|(out, _)| *out < end);
+        let end = match self.points.get(i) {
+            Some((_, src)) => *src,
+            None => self.points.last().map_or(0, |(_, src)| *src),
+        };
+
+        (start, usize::max(start, end))
+    }
+}
@@ -0,0 +1,152 @@
+//! Explains how adjacent tokens combine in the input.
+//!
+//! `vidyut_cheda` does not report which sandhi rule it undid between two tokens, so we recover
+//! the rule by comparing the tokens with the input. For each pair of adjacent tokens, we look for
+//! a rule in `sandhi-rules.csv` that turns the two tokens into the input they cover, and we ask
+//! `vidyut_sandhi::Splitter` whether the split is valid.
+use pyo3::prelude::*;
+use vidyut_kosha::semantics::Pada;
+
+use crate::cheda::align::Span;
+use crate::cheda::data::{Data, Rule};
+
+/// A sandhi rule: `first` and `second` combine to produce `result`.
+#[pyclass(name = "SandhiRule", get_all)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PySandhiRule {
+    /// The end of the first word.
+    pub first: String,
+    /// The start of the second word.
+    pub second: String,
+    /// The combined sounds.
+    pub result: String,
+}
+
+#[pymethods]
+impl PySandhiRule {
+    fn __repr__(&self) -> String {
+        format!(
+            "SandhiRule(first='{}', second='{}', result='{}')",
+            self.first, self.second, self.result
+        )
+    }
+}
+
+impl From<&Rule> for PySandhiRule {
+    fn from(r: &Rule) -> Self {
+        Self {
+            first: r.first.clone(),
+            second: r.second.clone(),
+            result: r.result.clone(),
+        }
+    }
+}
+
+/// How two adjacent tokens combine in the input.
+#[pyclass(name = "Boundary", get_all)]
+#[derive(Clone, Debug)]
+pub struct PyBoundary {
+    /// The char offset in the input where the first token begins.
+    pub start: usize,
+    /// The char offset in the input where the second token ends.
+    pub end: usize,
+    /// The part of the input that the two tokens cover.
+    pub surface: String,
+    /// The underlying form of the first token.
+    pub first: String,
+    /// The underlying form of the second token.
+    pub second: String,
+    /// The sandhi rule that joins `first` and `second`, or `None` if they are simply juxtaposed
+    /// or if no rule explains `surface`.
+    pub rule: Option<PySandhiRule>,
+    /// Whether the split is phonetically valid according to `vidyut_sandhi`. Juxtaposed tokens
+    /// are always valid.
+    pub is_valid: bool,
+}
+
+#[pymethods]
+impl PyBoundary {
+    fn __repr__(&self) -> String {
+        let rule = match &self.rule {
+            Some(r) => r.__repr__(),
+            None => "None".to_string(),
+        };
+        let is_valid = if self.is_valid { "True" } else { "False" };
+        format!(
+            "Boundary<(surface='{}', first='{}', second='{}', rule={}, is_valid={})>",
+            self.surface, self.first, self.second, rule, is_valid
+        )
+    }
+}
+
+/// Returns whether `surface` is `first` and `second` with only whitespace between them.
+fn is_juxtaposed(surface: &str, first: &str, second: &str) -> bool {
+    match surface
+        .strip_prefix(first)
+        .and_then(|s| s.strip_suffix(second))
+    {
+        Some(middle) => middle.chars().all(char::is_whitespace),
+        None => false,
+    }
+}
+
+/// Returns whether `rule` turns `first` and `second` into `surface`.
+///
+/// Rules often mark a word break with a space, as in `o g`, but the input might not have one, so
+/// we ignore whitespace when comparing.
+fn explains(rule: &Rule, surface: &str, first: &str, second: &str) -> bool {
+    let head = match first.strip_suffix(rule.first.as_str()) {
+        Some(head) => head,
+        None => return false,
+    };
+    let tail = match second.strip_prefix(rule.second.as_str()) {
+        Some(tail) => tail,
+        None => return false,
+    };
+    let expected = format!("{}{}{}", head, rule.result, tail);
+    let without_space = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    without_space(&expected) == without_space(surface)
+}
+
+/// Returns the boundaries between adjacent `tokens` in the SLP1 string `text`.
+///
+/// `tokens` is the output of `Chedaka.run` for `text`.
+pub(crate) fn explain(data: &Data, text: &str, tokens: &[(String, Pada, Span)]) -> Vec<PyBoundary> {
+    let chars: Vec<char> = text.chars().collect();
+    tokens
+        .windows(2)
+        .map(|pair| {
+            let (first, _, (start, _)) = &pair[0];
+            let (second, _, (_, end)) = &pair[1];
+            let surface: String = chars[*start..*end].iter().collect();
+
+            let (rule, is_valid) = if is_juxtaposed(&surface, first, second) {
+                (None, true)
+            } else {
+                let rule = data
+                    .rules
+                    .iter()
+                    .filter(|r| explains(r, &surface, first, second))
+                    .max_by_key(|r| r.first.len() + r.second.len())
+                    .map(PySandhiRule::from);
+                let is_valid = (1..surface.len())
+                    .filter(|i| surface.is_char_boundary(*i))
+                    .flat_map(|i| data.splitter.split_at(&surface, i))
+                    .find(|s| s.first() == first && s.second().trim_start() == second)
+                    .map(|s| s.is_valid())
+                    .unwrap_or(rule.is_some());
+                (rule, is_valid)
+            };
+
+            PyBoundary {
+                start: *start,
+                end: *end,
+                surface,
+                first: first.clone(),
+                second: second.clone(),
+                rule,
+                is_valid,
+            }
+        })
+        .collect()
+}
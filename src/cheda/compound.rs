@@ -0,0 +1,151 @@
+//! Groups the members of a compound into a single unit.
+//!
+//! `Chedaka` returns each compound member as its own token, and marks every member except the
+//! last with `is_purvapada`. So, a compound is a run of *pūrvapada* tokens followed by one more
+//! token.
+use pyo3::prelude::*;
+
+use crate::cheda::PyToken;
+use crate::kosha::semantics::PyVacana;
+
+/// A coarse type of compound.
+#[pyclass(name = "SamasaType")]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PySamasaType {
+    /// A determinative compound, such as *rājapuruṣa* ("the king's man").
+    Tatpurusha,
+    /// A coordinative compound, such as *rāmalakṣmaṇau* ("Rama and Lakshmana").
+    Dvandva,
+    /// An exocentric compound, such as *bahudhana* ("one who has much wealth").
+    Bahuvrihi,
+    /// An adverbial compound, such as *yathāśakti* ("according to one's ability").
+    Avyayibhava,
+}
+
+/// A compound and its members.
+#[pyclass(name = "Compound", get_all)]
+#[derive(Clone)]
+pub struct PyCompound {
+    /// The members of the compound, in order.
+    pub members: Vec<PyToken>,
+    /// The char offset in the input where the compound begins.
+    pub start: usize,
+    /// The char offset in the input where the compound ends.
+    pub end: usize,
+    /// The part of the input that the compound covers.
+    pub surface: String,
+    /// A heuristic guess at the type of the compound.
+    pub samasa_type: Option<PySamasaType>,
+}
+
+#[pymethods]
+impl PyCompound {
+    fn __repr__(&self) -> String {
+        let members: Vec<_> = self.members.iter().map(|t| t.text.as_str()).collect();
+        let samasa_type = match self.samasa_type {
+            Some(t) => format!("SamasaType.{:?}", t),
+            None => "None".to_string(),
+        };
+        format!(
+            "Compound<(members={:?}, start={}, end={}, samasa_type={})>",
+            members, self.start, self.end, samasa_type
+        )
+    }
+}
+
+/// Prefixes that usually start a *bahuvrīhi*, as in *bahudhana*, *nirdhana*, or *sudhana*.
+const BAHUVRIHI_PREFIXES: &[&str] = &["a", "an", "bahu", "dus", "nis", "sa", "su"];
+
+/// Indeclinables that usually start an *avyayībhāva*, as in *upakṛṣṇa* or *yathāśakti*.
+const AVYAYIBHAVA_PREFIXES: &[&str] = &["aDi", "anu", "upa", "prati", "yaTA", "yAvat"];
+
+/// Guesses the type of the compound with the given members.
+///
+/// This guess looks only at the first and last members, so it is often wrong. We guess a
+/// *bahuvrīhi* if the first member is a common *bahuvrīhi* prefix, an *avyayībhāva* if it is a
+/// common *avyayībhāva* prefix, and a *dvandva* if the number of the last member matches the
+/// number of members. Otherwise, we guess a *tatpuruṣa*.
+///
+/// We check the first member by its lemma, since `Chedaka` gives every non-final member as a
+/// subanta, even if it is an indeclinable.
+fn guess_type(members: &[PyToken]) -> Option<PySamasaType> {
+    let (first, last) = match members {
+        [first, .., last] => (first, last),
+        _ => return None,
+    };
+    if first.is_unknown || last.is_unknown {
+        return None;
+    }
+
+    let num_members = members.len();
+    let is_dvandva = match last.info.vacana() {
+        Some(PyVacana::Dvi) => num_members == 2,
+        Some(PyVacana::Bahu) => num_members > 2,
+        _ => false,
+    };
+
+    let first_lemma = first.lemma().unwrap_or_default();
+    if BAHUVRIHI_PREFIXES.contains(&first_lemma.as_str()) {
+        Some(PySamasaType::Bahuvrihi)
+    } else if AVYAYIBHAVA_PREFIXES.contains(&first_lemma.as_str()) {
+        Some(PySamasaType::Avyayibhava)
+    } else if is_dvandva {
+        Some(PySamasaType::Dvandva)
+    } else {
+        Some(PySamasaType::Tatpurusha)
+    }
+}
+
+fn make_compound(members: Vec<PyToken>) -> PyCompound {
+    let start = members.first().map_or(0, |t| t.start);
+    let end = members.last().map_or(0, |t| t.end);
+    let mut surface = String::new();
+    let mut prev_end = start;
+    for t in &members {
+        // Members can overlap due to sandhi, so skip any chars that we've already added.
+        let skip = prev_end.saturating_sub(t.start);
+        surface.extend(t.surface.chars().skip(skip));
+        prev_end = prev_end.max(t.end);
+    }
+
+    PyCompound {
+        samasa_type: guess_type(&members),
+        members,
+        start,
+        end,
+        surface,
+    }
+}
+
+/// Group the given tokens into compounds.
+///
+/// Return a list of `Token` and `Compound` objects in input order. A compound is a run of tokens
+/// whose `info` has `is_purvapada` set, followed by the token that ends the compound. Tokens that
+/// are not part of a compound are returned as-is.
+#[pyfunction]
+#[pyo3(name = "group_compounds")]
+pub fn py_group_compounds(py: Python, tokens: Vec<PyToken>) -> Vec<PyObject> {
+    let mut ret = Vec::new();
+    let mut members: Vec<PyToken> = Vec::new();
+    for token in tokens {
+        let is_purvapada = token.info.is_purvapada();
+        members.push(token);
+        if !is_purvapada {
+            ret.push(members);
+            members = Vec::new();
+        }
+    }
+    if !members.is_empty() {
+        ret.push(members);
+    }
+
+    ret.into_iter()
+        .map(|mut members| {
+            if members.len() == 1 {
+                members.remove(0).into_py(py)
+            } else {
+                make_compound(members).into_py(py)
+            }
+        })
+        .collect()
+}
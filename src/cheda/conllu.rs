@@ -0,0 +1,276 @@
+//! Converts tokens to CoNLL-U and JSON.
+//!
+//! CoNLL-U is the format that Universal Dependencies (UD) uses for its treebanks. For details,
+//! see <https://universaldependencies.org/format.html>.
+use pyo3::prelude::*;
+use serde_json::json;
+
+use crate::cheda::PyToken;
+use crate::kosha::semantics::{
+    PyLakara, PyLinga, PyPada, PyPartOfSpeech, PyPurusha, PyVacana, PyVibhakti,
+};
+
+/// The placeholder for an empty CoNLL-U field.
+const EMPTY: &str = "_";
+
+/// Returns the UD part-of-speech tag for `info`.
+pub(crate) fn upos(info: &PyPada) -> &'static str {
+    match info.pos() {
+        Some(PyPartOfSpeech::Subanta) => "NOUN",
+        Some(PyPartOfSpeech::Tinanta) => "VERB",
+        Some(PyPartOfSpeech::Avyaya) => "ADV",
+        None => "X",
+    }
+}
+
+/// Returns the UD features for a *lakāra*.
+///
+/// UD has no tag for the benedictive or the injunctive, so we treat the benedictive as an
+/// optative and give the injunctive no tense or mood.
+fn lakara_feats(lakara: &PyLakara) -> &'static [(&'static str, &'static str)] {
+    use PyLakara::*;
+    match lakara {
+        Lat => &[("Mood", "Ind"), ("Tense", "Pres")],
+        Lit => &[("Aspect", "Perf"), ("Mood", "Ind"), ("Tense", "Past")],
+        Lut | Lrt => &[("Mood", "Ind"), ("Tense", "Fut")],
+        Let => &[("Mood", "Sub")],
+        Lot => &[("Mood", "Imp")],
+        Lan => &[("Aspect", "Imp"), ("Mood", "Ind"), ("Tense", "Past")],
+        AshirLin | VidhiLin => &[("Mood", "Opt")],
+        Lun => &[("Mood", "Ind"), ("Tense", "Past")],
+        LunNoAgama => &[],
+        Lrn => &[("Mood", "Cnd")],
+    }
+}
+
+/// Returns the UD features for `info` in CoNLL-U format, sorted by name.
+pub(crate) fn feats(info: &PyPada) -> String {
+    let mut feats: Vec<(&str, &str)> = Vec::new();
+
+    if let Some(v) = info.vibhakti() {
+        let case = match v {
+            PyVibhakti::V1 => "Nom",
+            PyVibhakti::V2 => "Acc",
+            PyVibhakti::V3 => "Ins",
+            PyVibhakti::V4 => "Dat",
+            PyVibhakti::V5 => "Abl",
+            PyVibhakti::V6 => "Gen",
+            PyVibhakti::V7 => "Loc",
+            PyVibhakti::Sambodhana => "Voc",
+        };
+        feats.push(("Case", case));
+    }
+    if let Some(l) = info.linga() {
+        let gender = match l {
+            PyLinga::Pum => "Masc",
+            PyLinga::Stri => "Fem",
+            PyLinga::Napumsaka => "Neut",
+        };
+        feats.push(("Gender", gender));
+    }
+    if let Some(v) = info.vacana() {
+        let number = match v {
+            PyVacana::Eka => "Sing",
+            PyVacana::Dvi => "Dual",
+            PyVacana::Bahu => "Plur",
+        };
+        feats.push(("Number", number));
+    }
+    if let Some(p) = info.purusha() {
+        let person = match p {
+            PyPurusha::Prathama => "3",
+            PyPurusha::Madhyama => "2",
+            PyPurusha::Uttama => "1",
+        };
+        feats.push(("Person", person));
+    }
+    if let Some(l) = info.lakara() {
+        feats.extend(lakara_feats(l).iter().copied());
+    }
+    if info.pos() == Some(&PyPartOfSpeech::Tinanta) {
+        feats.push(("VerbForm", "Fin"));
+    }
+
+    if feats.is_empty() {
+        return EMPTY.to_string();
+    }
+    feats.sort_unstable();
+    let feats: Vec<_> = feats.iter().map(|(k, v)| format!("{k}={v}")).collect();
+    feats.join("|")
+}
+
+/// The UD features that `feats` can produce.
+const KNOWN_FEATS: &[&str] = &[
+    "Aspect", "Case", "Gender", "Mood", "Number", "Person", "Tense", "VerbForm",
+];
+
+/// Returns `feats` with only the features in `KNOWN_FEATS`, sorted by name.
+///
+/// Corpora often tag features that we cannot predict, so we drop them before comparing.
+pub(crate) fn known_feats(feats: &str) -> String {
+    let mut known: Vec<_> = feats
+        .split('|')
+        .filter(|f| match f.split_once('=') {
+            Some((key, _)) => KNOWN_FEATS.contains(&key),
+            None => false,
+        })
+        .collect();
+    if known.is_empty() {
+        return EMPTY.to_string();
+    }
+    known.sort_unstable();
+    known.join("|")
+}
+
+/// A syntactic word in a CoNLL-U file.
+pub(crate) struct Word {
+    pub form: String,
+    pub lemma: String,
+    pub feats: String,
+}
+
+/// A sentence in a CoNLL-U file.
+pub(crate) struct Sentence {
+    /// The value of the `sent_id` comment, if any.
+    pub sent_id: Option<String>,
+    /// The value of the `text` comment, or the sentence's surface forms joined by spaces.
+    pub text: String,
+    pub words: Vec<Word>,
+}
+
+/// Reads the sentences in the CoNLL-U string `input`.
+///
+/// We skip empty nodes. If a sentence has no `text` comment, we rebuild its text from its
+/// multiword tokens and the words outside of them.
+pub(crate) fn read(input: &str) -> Vec<Sentence> {
+    let mut ret = Vec::new();
+    let mut lines = input.lines().peekable();
+    while lines.peek().is_some() {
+        let mut sent_id = None;
+        let mut text = None;
+        let mut words = Vec::new();
+        let mut surfaces = Vec::new();
+        // The last word ID covered by the current multiword token.
+        let mut range_end = 0;
+
+        for line in lines.by_ref() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some((key, value)) = comment.split_once('=') {
+                    match key.trim() {
+                        "sent_id" => sent_id = Some(value.trim().to_string()),
+                        "text" => text = Some(value.trim().to_string()),
+                        _ => (),
+                    }
+                }
+                continue;
+            }
+
+            let fields: Vec<_> = line.split('\t').collect();
+            if fields.len() < 6 {
+                continue;
+            }
+            let (id, form) = (fields[0], fields[1]);
+            if let Some((_, end)) = id.split_once('-') {
+                range_end = end.parse().unwrap_or(0);
+                surfaces.push(form.to_string());
+                continue;
+            }
+            let id: usize = match id.parse() {
+                Ok(id) => id,
+                // An empty node, such as `1.1`.
+                Err(_) => continue,
+            };
+
+            if id > range_end {
+                surfaces.push(form.to_string());
+            }
+            words.push(Word {
+                form: form.to_string(),
+                lemma: fields[2].to_string(),
+                feats: fields[5].to_string(),
+            });
+        }
+
+        if !words.is_empty() {
+            ret.push(Sentence {
+                sent_id,
+                text: text.unwrap_or_else(|| surfaces.join(" ")),
+                words,
+            });
+        }
+    }
+    ret
+}
+
+/// Replaces characters that would break a CoNLL-U line.
+fn escape(field: &str) -> String {
+    if field.is_empty() {
+        return EMPTY.to_string();
+    }
+    field.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Appends `tokens` to `out` as one CoNLL-U sentence.
+///
+/// `comments` are written first as `# key = value` lines. We leave HEAD and DEPREL empty, since
+/// `Chedaka` does not parse syntax, and we store each token's char span in MISC as `TokenRange`.
+pub(crate) fn write_sentence(out: &mut String, comments: &[(&str, &str)], tokens: &[PyToken]) {
+    for (key, value) in comments {
+        out.push_str(&format!("# {} = {}\n", key, escape(value)));
+    }
+    for (i, t) in tokens.iter().enumerate() {
+        let lemma = t.info.lemma().unwrap_or_default();
+        let fields = [
+            (i + 1).to_string(),
+            escape(&t.text),
+            escape(&lemma),
+            upos(&t.info).to_string(),
+            EMPTY.to_string(),
+            feats(&t.info),
+            EMPTY.to_string(),
+            EMPTY.to_string(),
+            EMPTY.to_string(),
+            format!("TokenRange={}:{}", t.start, t.end),
+        ];
+        out.push_str(&fields.join("\t"));
+        out.push('\n');
+    }
+    out.push('\n');
+}
+
+/// Return the given tokens as a CoNLL-U sentence.
+///
+/// FORM is the token's `text`, LEMMA is its `lemma`, and FEATS are Universal Dependencies
+/// features derived from its `info`. MISC holds the token's char span as `TokenRange=start:end`.
+#[pyfunction]
+#[pyo3(name = "to_conllu")]
+pub fn py_to_conllu(tokens: Vec<PyToken>) -> String {
+    let mut ret = String::new();
+    write_sentence(&mut ret, &[], &tokens);
+    ret
+}
+
+/// Return the given tokens as a JSON list.
+#[pyfunction]
+#[pyo3(name = "to_json")]
+pub fn py_to_json(tokens: Vec<PyToken>) -> String {
+    let tokens: Vec<_> = tokens
+        .iter()
+        .map(|t| {
+            json!({
+                "text": t.text,
+                "lemma": t.info.lemma(),
+                "start": t.start,
+                "end": t.end,
+                "surface": t.surface,
+                "is_unknown": t.is_unknown,
+                "info": t.info.to_json(),
+            })
+        })
+        .collect();
+    json!(tokens).to_string()
+}
@@ -0,0 +1,209 @@
+//! Constraints on how `Chedaka` may segment its input.
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use vidyut_sandhi::Splitter;
+
+use crate::cheda::align::Span;
+
+/// The most chars that sandhi changes on either side of a word boundary.
+const MAX_CHANGE: usize = 3;
+
+/// Constraints on how `Chedaka.run` may segment its input.
+///
+/// All offsets are char offsets into the text passed to `run`.
+#[pyclass(name = "Constraints", get_all)]
+#[derive(Clone, Debug, Default)]
+pub struct PyConstraints {
+    /// Half-open spans that must be parsed as exactly one token.
+    pub tokens: Vec<(usize, usize)>,
+    /// Offsets where one token must end and the next must begin.
+    pub boundaries: Vec<usize>,
+}
+
+#[pymethods]
+impl PyConstraints {
+    /// Create a new set of constraints.
+    #[new]
+    #[pyo3(signature = (*, tokens = Vec::new(), boundaries = Vec::new()))]
+    fn new(tokens: Vec<(usize, usize)>, boundaries: Vec<usize>) -> Self {
+        Self { tokens, boundaries }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Constraints(tokens={:?}, boundaries={:?})",
+            self.tokens, self.boundaries
+        )
+    }
+}
+
+/// A piece of the input that we segment on its own.
+pub(crate) enum Piece {
+    /// A span that the decoder may segment freely.
+    Free(Span),
+    /// A span that must be a single token.
+    Token(Span),
+}
+
+impl Piece {
+    /// Returns the span of this piece.
+    pub(crate) fn span(&self) -> Span {
+        match self {
+            Piece::Free(span) | Piece::Token(span) => *span,
+        }
+    }
+}
+
+impl PyConstraints {
+    /// Splits an input of `len` chars into pieces that satisfy these constraints.
+    ///
+    /// This method raises a `ValueError` if a constraint is out of range, if two token spans
+    /// overlap, or if a boundary falls inside a token span.
+    pub(crate) fn pieces(&self, len: usize) -> PyResult<Vec<Piece>> {
+        let mut tokens = self.tokens.clone();
+        tokens.sort();
+
+        let mut prev_end = 0;
+        for &(start, end) in &tokens {
+            if start >= end || end > len {
+                let message =
+                    format!("Invalid token span ({start}, {end}) for input of length {len}.");
+                return Err(PyValueError::new_err(message));
+            }
+            if start < prev_end {
+                let message = format!("Token span ({start}, {end}) overlaps another token span.");
+                return Err(PyValueError::new_err(message));
+            }
+            prev_end = end;
+        }
+
+        let mut boundaries = self.boundaries.clone();
+        for &b in &boundaries {
+            if b > len {
+                let message = format!("Boundary {b} is out of range for input of length {len}.");
+                return Err(PyValueError::new_err(message));
+            }
+            if tokens.iter().any(|&(start, end)| start < b && b < end) {
+                let message = format!("Boundary {b} falls inside a token span.");
+                return Err(PyValueError::new_err(message));
+            }
+        }
+        boundaries.extend(tokens.iter().flat_map(|&(start, end)| [start, end]));
+        boundaries.extend([0, len]);
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        Ok(boundaries
+            .windows(2)
+            .map(|w| {
+                let span = (w[0], w[1]);
+                if tokens.binary_search(&span).is_ok() {
+                    Piece::Token(span)
+                } else {
+                    Piece::Free(span)
+                }
+            })
+            .collect())
+    }
+}
+
+/// A change to one end of a piece that undoes the sandhi across a forced boundary.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Edit {
+    /// The number of chars at this end of the piece to replace.
+    pub len: usize,
+    /// The chars to replace them with.
+    pub text: String,
+}
+
+/// The changes that turn a piece into its underlying form.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Ends {
+    pub head: Edit,
+    pub tail: Edit,
+}
+
+impl Ends {
+    /// Returns the underlying form of `surface`, or `None` if the two edits overlap.
+    pub(crate) fn apply(&self, surface: &str) -> Option<String> {
+        let chars: Vec<char> = surface.chars().collect();
+        let core_end = chars.len().checked_sub(self.tail.len)?;
+        if self.head.len > core_end {
+            return None;
+        }
+        let core: String = chars[self.head.len..core_end].iter().collect();
+        Some(format!("{}{}{}", self.head.text, core, self.tail.text))
+    }
+
+    /// Maps `span`, which uses offsets into the underlying form of a piece with `len` chars, to
+    /// offsets into the piece itself.
+    pub(crate) fn to_surface(&self, span: Span, len: usize) -> Span {
+        let head_len = self.head.text.chars().count();
+        let core_len = len - self.head.len - self.tail.len;
+        let map = |i: usize| {
+            if i <= head_len {
+                usize::min(i, self.head.len)
+            } else if i <= head_len + core_len {
+                i - head_len + self.head.len
+            } else {
+                self.head.len + core_len + usize::min(i - head_len - core_len, self.tail.len)
+            }
+        };
+        (map(span.0), map(span.1))
+    }
+}
+
+/// Returns the ways to undo sandhi between the SLP1 strings `left` and `right` such that the input
+/// is still split exactly between them.
+///
+/// Each item has an edit for the end of `left` and an edit for the start of `right`. The first
+/// item is always a pair of empty edits, which leaves both strings as they are.
+pub(crate) fn junctions(splitter: &Splitter, left: &str, right: &str) -> Vec<(Edit, Edit)> {
+    let mut ret = vec![(Edit::default(), Edit::default())];
+    if left.is_empty() || right.is_empty() || !left.is_ascii() || !right.is_ascii() {
+        return ret;
+    }
+
+    let combined = format!("{}{}", left, right);
+    let cut = left.len();
+    let lo = usize::max(1, cut.saturating_sub(MAX_CHANGE));
+    let hi = usize::min(combined.len(), cut + MAX_CHANGE);
+    for i in lo..hi {
+        for split in splitter.split_at(&combined, i) {
+            let (first, second) = (split.first(), split.second().trim_start());
+            if !split.is_valid() || first.is_empty() || second.is_empty() {
+                continue;
+            }
+            if combined.len().checked_sub(second.len()) != Some(cut) {
+                continue;
+            }
+
+            let prefix_len = common_prefix_len(left, first);
+            let tail = Edit {
+                len: left.len() - prefix_len,
+                text: first[prefix_len..].to_string(),
+            };
+            let suffix_len = common_suffix_len(right, second);
+            let head = Edit {
+                len: right.len() - suffix_len,
+                text: second[..second.len() - suffix_len].to_string(),
+            };
+            if !ret.contains(&(tail.clone(), head.clone())) {
+                ret.push((tail, head));
+            }
+        }
+    }
+    ret
+}
+
+fn common_prefix_len(x: &str, y: &str) -> usize {
+    x.bytes().zip(y.bytes()).take_while(|(a, b)| a == b).count()
+}
+
+fn common_suffix_len(x: &str, y: &str) -> usize {
+    x.bytes()
+        .rev()
+        .zip(y.bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
@@ -0,0 +1,86 @@
+//! Data that `vidyut_cheda::Chedaka` loads but does not expose.
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use std::path::Path;
+use vidyut_kosha::Kosha;
+use vidyut_sandhi::Splitter;
+
+/// A row in `sandhi-rules.csv`: `first` and `second` combine to produce `result`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Rule {
+    pub first: String,
+    pub second: String,
+    pub result: String,
+}
+
+/// The sandhi rules and lexicon in a `Chedaka` data directory.
+///
+/// We load these on demand for features that need more than `Chedaka::run` provides. They use
+/// the same files as `Chedaka` itself, so the two always agree.
+pub(crate) struct Data {
+    pub splitter: Splitter,
+    /// The rules that `splitter` was built from. `Splitter` does not expose them.
+    pub rules: Vec<Rule>,
+    pub kosha: Kosha,
+}
+
+impl Data {
+    /// Reads data from the `Chedaka` data directory at `base`.
+    pub(crate) fn new(base: &Path) -> PyResult<Self> {
+        let sandhi_path = base.join("sandhi-rules.csv");
+        let splitter = match Splitter::from_csv(&sandhi_path) {
+            Ok(s) => s,
+            Err(e) => {
+                let message = format!("Could not read '{}': {:?}", sandhi_path.display(), e);
+                return Err(PyOSError::new_err(message));
+            }
+        };
+
+        let rules = parse_rules(&sandhi_path)?;
+
+        let kosha_path = base.join("kosha");
+        let kosha = match Kosha::new(&kosha_path) {
+            Ok(k) => k,
+            Err(_) => {
+                let message = format!("Could not read kosha at '{}'.", kosha_path.display());
+                return Err(PyOSError::new_err(message));
+            }
+        };
+
+        Ok(Self {
+            splitter,
+            rules,
+            kosha,
+        })
+    }
+}
+
+/// Reads the rules in the sandhi rules CSV at `path`.
+///
+/// We read the file with the same CSV parser that `Splitter::from_csv` uses, so that our rules
+/// match the splitter's. Unlike the splitter, we raise an `OSError` on a row with fewer than three
+/// fields instead of panicking.
+fn parse_rules(path: &Path) -> PyResult<Vec<Rule>> {
+    let to_err = |message: String| {
+        let message = format!("Could not read '{}': {}", path.display(), message);
+        PyOSError::new_err(message)
+    };
+
+    let mut reader = csv::Reader::from_path(path).map_err(|e| to_err(e.to_string()))?;
+    let mut rules = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| to_err(e.to_string()))?;
+        match (record.get(0), record.get(1), record.get(2)) {
+            (Some(first), Some(second), Some(result)) => rules.push(Rule {
+                first: first.to_string(),
+                second: second.to_string(),
+                result: result.to_string(),
+            }),
+            _ => {
+                let line = record.position().map_or(0, |p| p.line());
+                return Err(to_err(format!("line {} has fewer than 3 fields", line)));
+            }
+        }
+    }
+    Ok(rules)
+}
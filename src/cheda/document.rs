@@ -0,0 +1,126 @@
+//! Splits a document into sentences and half-verses.
+//!
+//! We split on the *daṇḍa* (`।` or `|`) and the double *daṇḍa* (`॥` or `||`), and we treat a blank
+//! line as the end of a paragraph. A verse number after a double *daṇḍa*, as in `॥ १ ॥` or
+//! `|| 1.2 ||`, belongs to the unit it closes, even if a single *daṇḍa* already ended that unit.
+//! Single newlines are ordinary whitespace, since prose is often hard-wrapped.
+
+use crate::cheda::align::Span;
+
+/// A unit of text that we segment on its own.
+pub(crate) struct Unit {
+    /// The char span of the unit's text, excluding surrounding whitespace and punctuation.
+    pub span: Span,
+    /// Whether the unit closes a verse.
+    pub ends_verse: bool,
+    /// The verse number that follows the unit, if any.
+    pub verse_number: Option<String>,
+}
+
+fn is_danda(c: char) -> bool {
+    c == '।' || c == '|'
+}
+
+fn is_digit(c: char) -> bool {
+    c.is_ascii_digit() || ('०'..='९').contains(&c)
+}
+
+/// Returns the length of the double *daṇḍa* that starts at `chars[i]`, if any.
+fn double_danda_len(chars: &[char], i: usize) -> Option<usize> {
+    match chars.get(i) {
+        Some('॥') => Some(1),
+        Some('|') if chars.get(i + 1) == Some(&'|') => Some(2),
+        Some('।') if chars.get(i + 1) == Some(&'।') => Some(2),
+        _ => None,
+    }
+}
+
+fn skip_whitespace(chars: &[char], mut i: usize) -> usize {
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// Returns the non-empty units in `text`, in order.
+pub(crate) fn split_units(text: &str) -> Vec<Unit> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut units = Vec::new();
+
+    let mut push = |start: usize, end: usize, ends_verse: bool, verse_number: Option<String>| {
+        let start = skip_whitespace(&chars, start);
+        let mut end = end;
+        while end > start && chars[end - 1].is_whitespace() {
+            end -= 1;
+        }
+        if end > start {
+            units.push(Unit {
+                span: (start, end),
+                ends_verse,
+                verse_number,
+            });
+        } else if verse_number.is_some() {
+            // A bare verse number, as in `अर्जुनस् ।\n॥ १ ॥`, closes the unit before it.
+            if let Some(last) = units.last_mut().filter(|u| u.verse_number.is_none()) {
+                last.ends_verse = true;
+                last.verse_number = verse_number;
+            }
+        }
+    };
+
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(len) = double_danda_len(&chars, i) {
+            let end = i;
+            i = skip_whitespace(&chars, i + len);
+
+            let mut verse_number = None;
+            let digits_start = i;
+            while i < chars.len() && (is_digit(chars[i]) || chars[i] == '.') {
+                i += 1;
+            }
+            if i > digits_start {
+                verse_number = Some(chars[digits_start..i].iter().collect());
+                let j = skip_whitespace(&chars, i);
+                if let Some(len) = double_danda_len(&chars, j) {
+                    i = j + len;
+                }
+            }
+
+            push(start, end, true, verse_number);
+            start = i;
+        } else if is_danda(chars[i]) {
+            push(start, i, false, None);
+            i += 1;
+            start = i;
+        } else if chars[i] == '\n' && is_blank_line_after(&chars, i) {
+            push(start, i, false, None);
+            i += 1;
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    push(start, chars.len(), false, None);
+
+    units
+}
+
+/// Returns whether `line` contains a double *daṇḍa*, which closes a verse.
+pub(crate) fn has_verse_end(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().collect();
+    (0..chars.len()).any(|i| double_danda_len(&chars, i).is_some())
+}
+
+/// Returns whether the newline at `chars[i]` is followed by a line that contains only whitespace.
+fn is_blank_line_after(chars: &[char], i: usize) -> bool {
+    let mut j = i + 1;
+    while j < chars.len() && chars[j] != '\n' {
+        if !chars[j].is_whitespace() {
+            return false;
+        }
+        j += 1;
+    }
+    j < chars.len()
+}
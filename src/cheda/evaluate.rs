@@ -0,0 +1,270 @@
+//! Scores `Chedaka` against a gold corpus.
+//!
+//! We compare the predicted and gold words of each sentence as multisets, so a predicted word is
+//! correct if the gold sentence has a matching word that no other predicted word has claimed.
+//! Segmentation compares forms, lemma accuracy compares (form, lemma) pairs, and tag accuracy
+//! compares (form, lemma, features) triples.
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::Hash;
+use std::path::PathBuf;
+
+use crate::cheda::conllu::{self, known_feats};
+use crate::cheda::PyChedaka;
+use crate::lipi::{to_scheme, PyScheme};
+
+/// Precision, recall, and F1 for some task.
+#[pyclass(name = "Score", get_all)]
+#[derive(Clone, Debug, Default)]
+pub struct PyScore {
+    /// The number of predicted items that match a gold item.
+    pub num_correct: usize,
+    /// The number of predicted items.
+    pub num_predicted: usize,
+    /// The number of gold items.
+    pub num_gold: usize,
+}
+
+#[pymethods]
+impl PyScore {
+    /// The fraction of predicted items that are correct.
+    #[getter]
+    pub fn precision(&self) -> f64 {
+        ratio(self.num_correct, self.num_predicted)
+    }
+
+    /// The fraction of gold items that were predicted.
+    #[getter]
+    pub fn recall(&self) -> f64 {
+        ratio(self.num_correct, self.num_gold)
+    }
+
+    /// The harmonic mean of `precision` and `recall`.
+    #[getter]
+    pub fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 {
+            0.0
+        } else {
+            2.0 * p * r / (p + r)
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Score(precision={:.4}, recall={:.4}, f1={:.4})",
+            self.precision(),
+            self.recall(),
+            self.f1()
+        )
+    }
+}
+
+fn ratio(x: usize, y: usize) -> f64 {
+    if y == 0 {
+        0.0
+    } else {
+        x as f64 / y as f64
+    }
+}
+
+/// The errors in a single sentence.
+#[pyclass(name = "SentenceErrors", get_all)]
+#[derive(Clone, Debug)]
+pub struct PySentenceErrors {
+    /// The sentence's `sent_id`, if any.
+    pub sent_id: Option<String>,
+    /// The sentence text, as given to `Chedaka`.
+    pub text: String,
+    /// Gold words that were not predicted, as `form/lemma/feats` strings in SLP1.
+    pub missing: Vec<String>,
+    /// Predicted words that are not in the gold data, as `form/lemma/feats` strings in SLP1.
+    pub extra: Vec<String>,
+    /// Why `Chedaka` could not segment this sentence, if it failed. If so, `missing` contains
+    /// every gold word and `extra` is empty.
+    pub error: Option<String>,
+}
+
+#[pymethods]
+impl PySentenceErrors {
+    fn __repr__(&self) -> String {
+        format!(
+            "SentenceErrors<(sent_id={:?}, missing={:?}, extra={:?}, error={:?})>",
+            self.sent_id, self.missing, self.extra, self.error
+        )
+    }
+}
+
+/// The result of `evaluate`.
+#[pyclass(name = "Report", get_all)]
+#[derive(Clone, Debug)]
+pub struct PyReport {
+    /// The number of sentences in the gold corpus.
+    pub num_sentences: usize,
+    /// Scores for word forms.
+    pub segmentation: PyScore,
+    /// Scores for (form, lemma) pairs.
+    pub lemma: PyScore,
+    /// Scores for (form, lemma, features) triples.
+    pub tag: PyScore,
+    /// Every sentence with at least one tag error, in corpus order.
+    pub errors: Vec<PySentenceErrors>,
+    /// The number of sentences that `Chedaka` could not segment. These sentences are also in
+    /// `errors`.
+    pub num_failed: usize,
+}
+
+#[pymethods]
+impl PyReport {
+    fn __repr__(&self) -> String {
+        format!(
+            "Report<(num_sentences={}, segmentation={}, lemma={}, tag={}, errors={}, num_failed={})>",
+            self.num_sentences,
+            self.segmentation.__repr__(),
+            self.lemma.__repr__(),
+            self.tag.__repr__(),
+            self.errors.len(),
+            self.num_failed
+        )
+    }
+}
+
+type Item = (String, String, String);
+
+/// Adds the counts for one sentence to `score`.
+fn update<T: Eq + Hash + Clone>(score: &mut PyScore, predicted: &[T], gold: &[T]) {
+    score.num_predicted += predicted.len();
+    score.num_gold += gold.len();
+    score.num_correct += predicted.len() - unmatched(predicted, gold).len();
+}
+
+/// Returns the items in `xs` that have no counterpart in `ys`, counting duplicates.
+fn unmatched<T: Eq + Hash + Clone>(xs: &[T], ys: &[T]) -> Vec<T> {
+    let mut counts: HashMap<&T, usize> = HashMap::new();
+    for y in ys {
+        *counts.entry(y).or_default() += 1;
+    }
+    xs.iter()
+        .filter(|x| match counts.get_mut(x) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns `form` with a final visarga replaced by `s`.
+///
+/// `vidyut` writes a word-final visarga as `s`, but corpora such as the DCS keep the visarga.
+fn normalize_form(form: &str) -> String {
+    match form.strip_suffix('H') {
+        Some(head) => format!("{head}s"),
+        None => form.to_string(),
+    }
+}
+
+fn forms(items: &[Item]) -> Vec<String> {
+    items.iter().map(|x| x.0.clone()).collect()
+}
+
+fn lemmas(items: &[Item]) -> Vec<(String, String)> {
+    items.iter().map(|x| (x.0.clone(), x.1.clone())).collect()
+}
+
+fn format_items(items: Vec<Item>) -> Vec<String> {
+    items
+        .into_iter()
+        .map(|(form, lemma, feats)| format!("{form}/{lemma}/{feats}"))
+        .collect()
+}
+
+/// Run `chedaka` on a gold corpus and score its output.
+///
+/// `gold_path` should be a CoNLL-U file, such as one exported from the Digital Corpus of Sanskrit
+/// (DCS). We run `chedaka` on the `text` comment of each sentence, or on its surface forms if
+/// there is no such comment. `scheme` is the scheme that the corpus uses.
+///
+/// We compare the predicted and gold words of each sentence as multisets, so word order does
+/// not affect the score. Segmentation compares forms, `lemma` compares (form, lemma) pairs, and
+/// `tag` compares (form, lemma, features) triples. For features, we compare only the Universal
+/// Dependencies features that `to_conllu` produces. Before we compare forms, we replace a final
+/// visarga with `s`, since `Chedaka` writes `arjunaH` as `arjunas`.
+///
+/// If `chedaka` cannot segment a sentence, e.g. because it contains characters that `chedaka`
+/// does not accept, we count it as a sentence with no predicted words, record it in `errors`,
+/// and continue with the next sentence.
+#[pyfunction]
+#[pyo3(name = "evaluate", signature = (chedaka, gold_path, *, scheme = PyScheme::Slp1))]
+pub fn py_evaluate(
+    chedaka: PyRef<PyChedaka>,
+    gold_path: PathBuf,
+    scheme: PyScheme,
+) -> PyResult<PyReport> {
+    let input = match fs::read_to_string(&gold_path) {
+        Ok(s) => s,
+        Err(e) => {
+            let message = format!("Could not read '{}': {}", gold_path.display(), e);
+            return Err(PyOSError::new_err(message));
+        }
+    };
+    let sentences = conllu::read(&input);
+
+    let mut report = PyReport {
+        num_sentences: sentences.len(),
+        segmentation: PyScore::default(),
+        lemma: PyScore::default(),
+        tag: PyScore::default(),
+        errors: Vec::new(),
+        num_failed: 0,
+    };
+    for sentence in sentences {
+        let (tokens, error) = match chedaka.segment(&sentence.text, scheme, PyScheme::Slp1) {
+            Ok(tokens) => (tokens, None),
+            Err(e) => {
+                report.num_failed += 1;
+                (Vec::new(), Some(e.to_string()))
+            }
+        };
+
+        let predicted: Vec<Item> = tokens
+            .iter()
+            .map(|t| {
+                let lemma = t.info.lemma().unwrap_or_default();
+                (normalize_form(&t.text), lemma, conllu::feats(&t.info))
+            })
+            .collect();
+        let gold: Vec<Item> = sentence
+            .words
+            .iter()
+            .map(|w| {
+                (
+                    normalize_form(&to_scheme(&w.form, scheme, PyScheme::Slp1)),
+                    to_scheme(&w.lemma, scheme, PyScheme::Slp1),
+                    known_feats(&w.feats),
+                )
+            })
+            .collect();
+
+        update(&mut report.segmentation, &forms(&predicted), &forms(&gold));
+        update(&mut report.lemma, &lemmas(&predicted), &lemmas(&gold));
+        update(&mut report.tag, &predicted, &gold);
+
+        let missing = unmatched(&gold, &predicted);
+        let extra = unmatched(&predicted, &gold);
+        if !missing.is_empty() || !extra.is_empty() || error.is_some() {
+            report.errors.push(PySentenceErrors {
+                sent_id: sentence.sent_id,
+                text: sentence.text,
+                missing: format_items(missing),
+                extra: format_items(extra),
+                error,
+            });
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,91 @@
+//! Joins words with sandhi.
+//!
+//! This is the inverse of what `Chedaka` does: given a list of words, we apply the rules in
+//! `sandhi-rules.csv` between each pair of adjacent words to produce continuous text.
+use pyo3::prelude::*;
+use std::collections::HashSet;
+
+use crate::cheda::data::Rule;
+use crate::cheda::PyToken;
+
+/// A word to join, either as a `Token` or as a string.
+#[derive(FromPyObject)]
+pub enum Word {
+    Token(PyToken),
+    Text(String),
+}
+
+impl Word {
+    pub fn into_text(self) -> String {
+        match self {
+            Word::Token(t) => t.text,
+            Word::Text(s) => s,
+        }
+    }
+}
+
+/// Returns every way to join `first` and `second`, with the most specific rules first.
+///
+/// If no rule applies, the words are separated by a space.
+fn join_pair(rules: &[Rule], first: &str, second: &str) -> Vec<String> {
+    let mut matches: Vec<&Rule> = rules
+        .iter()
+        .filter(|r| first.ends_with(r.first.as_str()) && second.starts_with(r.second.as_str()))
+        .collect();
+    // `sort_by_key` is stable, so ties keep the order of the rules file.
+    matches.sort_by_key(|r| std::cmp::Reverse(r.first.len() + r.second.len()));
+
+    let mut seen = HashSet::new();
+    let mut ret = Vec::new();
+    for r in matches {
+        let head = &first[..first.len() - r.first.len()];
+        let tail = &second[r.second.len()..];
+        let joined = format!("{head}{}{tail}", r.result);
+        if seen.insert(joined.clone()) {
+            ret.push(joined);
+        }
+    }
+    if ret.is_empty() {
+        ret.push(format!("{first} {second}"));
+    }
+    ret
+}
+
+/// Joins `words` with the most specific rule at each boundary.
+pub(crate) fn join(rules: &[Rule], words: &[String]) -> String {
+    let (first, rest) = match words.split_first() {
+        Some(x) => x,
+        None => return String::new(),
+    };
+
+    let mut ret = first.clone();
+    for word in rest {
+        ret = join_pair(rules, &ret, word).swap_remove(0);
+    }
+    ret
+}
+
+/// Returns every way to join `words` with `rules`, starting with the result of `join`.
+///
+/// Each join sees the result of the join before it.
+pub(crate) fn join_all(rules: &[Rule], words: &[String]) -> Vec<String> {
+    let (first, rest) = match words.split_first() {
+        Some(x) => x,
+        None => return vec![String::new()],
+    };
+
+    let mut ret = vec![first.clone()];
+    for word in rest {
+        let mut seen = HashSet::new();
+        let mut next = Vec::new();
+        for prefix in &ret {
+            for joined in join_pair(rules, prefix, word) {
+                if seen.insert(joined.clone()) {
+                    next.push(joined);
+                }
+            }
+        }
+        ret = next;
+    }
+    ret
+}
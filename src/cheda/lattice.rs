@@ -0,0 +1,367 @@
+//! Exports the candidate words that `Chedaka` considers for some input.
+//!
+//! `vidyut_cheda` does not expose its search space, so we rebuild it with the same sandhi rules
+//! and lexicons: starting from the beginning of each word, we undo every sandhi split that the
+//! rules allow and keep every first half that a lexicon contains. The model's scores are private
+//! to `vidyut_cheda`, so instead of scores, we mark the candidates that `Chedaka.run` chose.
+use pyo3::prelude::*;
+use serde_json::json;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use vidyut_kosha::semantics::Pada;
+use vidyut_kosha::Kosha;
+
+use crate::cheda::align::Span;
+use crate::cheda::data::Data;
+use crate::kosha::semantics::PyPada;
+
+/// A candidate word in a `Lattice`.
+#[pyclass(name = "LatticeNode", get_all)]
+#[derive(Clone)]
+pub struct PyLatticeNode {
+    /// The index of this node in `Lattice.nodes`.
+    pub id: usize,
+    /// The underlying form of this candidate.
+    pub text: String,
+    /// The char offset in the input where this candidate begins.
+    pub start: usize,
+    /// The char offset in the input where this candidate ends.
+    pub end: usize,
+    /// Morphological information for this candidate.
+    pub info: PyPada,
+    /// Whether `Chedaka.run` chose this candidate.
+    pub in_best_path: bool,
+}
+
+#[pymethods]
+impl PyLatticeNode {
+    fn __repr__(&self) -> String {
+        format!(
+            "LatticeNode<(id={}, text='{}', start={}, end={}, info={})>",
+            self.id,
+            self.text,
+            self.start,
+            self.end,
+            self.info.__repr__()
+        )
+    }
+}
+
+/// A sandhi transition from one candidate word to the next.
+#[pyclass(name = "LatticeEdge", get_all)]
+#[derive(Clone)]
+pub struct PyLatticeEdge {
+    /// The ID of the node before the transition.
+    pub source: usize,
+    /// The ID of the node after the transition.
+    pub target: usize,
+    /// Whether the sandhi split is phonetically valid according to some basic heuristics.
+    pub is_valid: bool,
+}
+
+#[pymethods]
+impl PyLatticeEdge {
+    fn __repr__(&self) -> String {
+        let is_valid = if self.is_valid { "True" } else { "False" };
+        format!(
+            "LatticeEdge(source={}, target={}, is_valid={})",
+            self.source, self.target, is_valid
+        )
+    }
+}
+
+/// All of the candidate segmentations of some input.
+#[pyclass(name = "Lattice", get_all)]
+pub struct PyLattice {
+    /// The input text.
+    pub text: String,
+    /// All candidate words.
+    pub nodes: Vec<PyLatticeNode>,
+    /// All transitions between candidate words.
+    pub edges: Vec<PyLatticeEdge>,
+}
+
+#[pymethods]
+impl PyLattice {
+    /// Return this lattice as a JSON string.
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|n| {
+                json!({
+                    "id": n.id,
+                    "text": n.text,
+                    "start": n.start,
+                    "end": n.end,
+                    "info": n.info.to_json(),
+                    "in_best_path": n.in_best_path,
+                })
+            })
+            .collect();
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .map(|e| {
+                json!({
+                    "source": e.source,
+                    "target": e.target,
+                    "is_valid": e.is_valid,
+                })
+            })
+            .collect();
+
+        json!({
+            "text": self.text,
+            "nodes": nodes,
+            "edges": edges,
+        })
+        .to_string()
+    }
+
+    /// Return this lattice in the Graphviz DOT format.
+    ///
+    /// Nodes on the best path are drawn in bold, and invalid transitions are drawn dashed.
+    pub fn to_dot(&self) -> String {
+        let mut ret = String::from("digraph lattice {\n    rankdir=LR;\n");
+        for n in &self.nodes {
+            let label = format!(
+                "{}\\n{}\\n{}-{}",
+                n.text,
+                n.info.lemma().unwrap_or_default(),
+                n.start,
+                n.end
+            );
+            let style = if n.in_best_path { ", style=bold" } else { "" };
+            ret += &format!(
+                "    {} [label=\"{}\"{}];\n",
+                n.id,
+                label.replace('"', "\\\""),
+                style
+            );
+        }
+        for e in &self.edges {
+            let style = if e.is_valid { "" } else { " [style=dashed]" };
+            ret += &format!("    {} -> {}{};\n", e.source, e.target, style);
+        }
+        ret += "}\n";
+        ret
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Lattice<(text='{}', nodes={}, edges={})>",
+            self.text,
+            self.nodes.len(),
+            self.edges.len()
+        )
+    }
+}
+
+/// A point in the search: an offset into the chunk and the rest of the chunk with sandhi undone.
+type State = (usize, String);
+
+struct Builder<'a> {
+    data: &'a Data,
+    lexicons: &'a [Arc<Kosha>],
+    nodes: Vec<PyLatticeNode>,
+    /// Maps (source, target) to whether any split between them is valid.
+    edges: HashMap<(usize, usize), bool>,
+    /// Maps (text, start, end) to the IDs of the nodes with that text and span.
+    index: HashMap<(String, usize, usize), Vec<usize>>,
+}
+
+impl Builder<'_> {
+    /// Returns every analysis of `key` in all lexicons.
+    fn lookup(&self, key: &str) -> Vec<Pada> {
+        std::iter::once(&self.data.kosha)
+            .chain(self.lexicons.iter().map(|k| k.as_ref()))
+            .flat_map(|kosha| {
+                kosha
+                    .get_all(key)
+                    .iter()
+                    .filter_map(|packed| kosha.unpack(packed).ok())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn has_prefix(&self, prefix: &str) -> bool {
+        self.data.kosha.contains_prefix(prefix)
+            || self.lexicons.iter().any(|k| k.contains_prefix(prefix))
+    }
+
+    /// Adds a node for each analysis of `text` over `span` and returns their IDs.
+    fn add_nodes(&mut self, text: &str, span: Span) -> Vec<usize> {
+        let key = (text.to_string(), span.0, span.1);
+        if let Some(ids) = self.index.get(&key) {
+            return ids.clone();
+        }
+
+        let mut ids = Vec::new();
+        for info in self.lookup(text) {
+            let id = self.nodes.len();
+            self.nodes.push(PyLatticeNode {
+                id,
+                text: text.to_string(),
+                start: span.0,
+                end: span.1,
+                info: info.into(),
+                in_best_path: false,
+            });
+            ids.push(id);
+        }
+        self.index.insert(key, ids.clone());
+        ids
+    }
+
+    fn add_edge(&mut self, source: usize, target: usize, is_valid: bool) {
+        *self.edges.entry((source, target)).or_insert(false) |= is_valid;
+    }
+
+    /// Adds all candidates for `chunk`, which starts at char offset `base` and contains no
+    /// whitespace. Returns the IDs of the nodes that begin and end the chunk.
+    fn add_chunk(&mut self, chunk: &str, base: usize) -> (Vec<usize>, Vec<usize>) {
+        let initial_state: State = (0, chunk.to_string());
+        let mut starts: HashMap<State, Vec<usize>> = HashMap::new();
+        let mut pending: Vec<(usize, State, bool)> = Vec::new();
+        let mut finals = Vec::new();
+
+        let mut seen = HashSet::from([initial_state.clone()]);
+        let mut queue = VecDeque::from([initial_state.clone()]);
+        while let Some((p, rest)) = queue.pop_front() {
+            let mut here = self.add_nodes(&rest, (base + p, base + p + rest.len()));
+            finals.extend(here.iter().copied());
+
+            for i in 1..rest.len() {
+                // Sandhi changes at most the last two sounds of the first word.
+                if i > 2 && !self.has_prefix(&rest[..i - 2]) {
+                    break;
+                }
+                for split in self.data.splitter.split_at(&rest, i) {
+                    let (first, second) = (split.first(), split.second());
+                    if second.is_empty() || second.len() >= rest.len() {
+                        continue;
+                    }
+
+                    let end = p + rest.len() - second.len();
+                    let ids = self.add_nodes(first, (base + p, base + end));
+                    if ids.is_empty() {
+                        continue;
+                    }
+
+                    let next: State = (end, second.to_string());
+                    for id in &ids {
+                        pending.push((*id, next.clone(), split.is_valid()));
+                    }
+                    here.extend(ids);
+                    if seen.insert(next.clone()) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+            starts.insert((p, rest), here);
+        }
+
+        for (source, next, is_valid) in pending {
+            for target in starts.get(&next).into_iter().flatten() {
+                self.add_edge(source, *target, is_valid);
+            }
+        }
+
+        let initials = starts.remove(&initial_state).unwrap_or_default();
+        (initials, finals)
+    }
+
+    /// Marks the nodes that `best` chose, adding nodes and edges for them if necessary.
+    fn mark_best_path(&mut self, best: Vec<(String, Pada, Span)>) {
+        let mut prev: Option<usize> = None;
+        for (text, info, (start, end)) in best {
+            let info: PyPada = info.into();
+            let found = self
+                .nodes
+                .iter()
+                .position(|n| n.text == text && n.info == info && n.start < end && start < n.end);
+            let id = match found {
+                Some(id) => id,
+                None => {
+                    let id = self.nodes.len();
+                    self.nodes.push(PyLatticeNode {
+                        id,
+                        text,
+                        start,
+                        end,
+                        info,
+                        in_best_path: false,
+                    });
+                    id
+                }
+            };
+            self.nodes[id].in_best_path = true;
+
+            if let Some(prev) = prev {
+                self.edges.entry((prev, id)).or_insert(true);
+            }
+            prev = Some(id);
+        }
+    }
+}
+
+/// Builds a lattice for the SLP1 string `text`.
+///
+/// `best` is the output of `Chedaka.run` for `text`.
+pub(crate) fn build(
+    data: &Data,
+    lexicons: &[Arc<Kosha>],
+    text: &str,
+    best: Vec<(String, Pada, Span)>,
+) -> PyLattice {
+    let mut builder = Builder {
+        data,
+        lexicons,
+        nodes: Vec::new(),
+        edges: HashMap::new(),
+        index: HashMap::new(),
+    };
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut prev_finals: Vec<usize> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let mut j = i;
+        while j < chars.len() && !chars[j].is_whitespace() {
+            j += 1;
+        }
+
+        let chunk: String = chars[i..j].iter().collect();
+        let (initials, finals) = builder.add_chunk(&chunk, i);
+        for source in &prev_finals {
+            for target in &initials {
+                builder.add_edge(*source, *target, true);
+            }
+        }
+        prev_finals = finals;
+        i = j;
+    }
+
+    builder.mark_best_path(best);
+
+    let mut edges: Vec<_> = builder.edges.into_iter().collect();
+    edges.sort_unstable();
+    PyLattice {
+        text: text.to_string(),
+        nodes: builder.nodes,
+        edges: edges
+            .into_iter()
+            .map(|((source, target), is_valid)| PyLatticeEdge {
+                source,
+                target,
+                is_valid,
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,75 @@
+//! Looks up words from user lexicons that the bundled `Chedaka` data does not know about.
+//!
+//! `vidyut_cheda` reads its lexicon from its data directory and offers no way to extend it. So,
+//! in the parts of the input that `vidyut_cheda` could not analyze, we find words from the user's
+//! lexicons ourselves, keep each one as a single token, and let `vidyut_cheda` segment the text
+//! between them.
+use std::sync::Arc;
+use vidyut_kosha::semantics::Pada;
+use vidyut_kosha::Kosha;
+
+use crate::cheda::align::Span;
+
+/// A word from a user lexicon that occurs in the input.
+pub(crate) struct Match {
+    /// The char span of the word in the input.
+    pub span: Span,
+    /// The word itself.
+    pub text: String,
+    /// The first analysis of the word in the first lexicon that contains it.
+    pub info: Pada,
+}
+
+/// Returns the leftmost-longest occurrences of keys from `lexicons` in `text`, in order.
+///
+/// Matches never overlap and never contain whitespace. Since we match keys against the input
+/// directly, a word matches only if sandhi has not changed its sounds.
+pub(crate) fn find_matches(text: &str, lexicons: &[Arc<Kosha>]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    if lexicons.is_empty() {
+        return matches;
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut longest = None;
+        let mut key = String::new();
+        for (j, c) in chars.iter().enumerate().skip(i) {
+            if c.is_whitespace() {
+                break;
+            }
+            key.push(*c);
+            if !lexicons.iter().any(|k| k.contains_prefix(&key)) {
+                break;
+            }
+            if let Some(info) = lookup(&key, lexicons) {
+                longest = Some((j + 1, key.clone(), info));
+            }
+        }
+
+        match longest {
+            Some((end, text, info)) => {
+                matches.push(Match {
+                    span: (i, end),
+                    text,
+                    info,
+                });
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+
+    matches
+}
+
+/// Returns the first analysis of `key` in `lexicons`, if any.
+fn lookup(key: &str, lexicons: &[Arc<Kosha>]) -> Option<Pada> {
+    lexicons.iter().find_map(|kosha| {
+        kosha
+            .get_all(key)
+            .iter()
+            .find_map(|packed| kosha.unpack(packed).ok())
+    })
+}
@@ -0,0 +1,188 @@
+//! Segments a file one line or one verse at a time.
+//!
+//! We read the file through a buffer and keep at most one unit of text in memory, so we can
+//! segment files that are much larger than the available memory. We read UTF-8 files directly and
+//! decode all other encodings with Python's own `open`.
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::cheda::document::{has_verse_end, split_units};
+use crate::cheda::{shift, PyChedaka, PyToken, WrappedError};
+use crate::lipi::PyScheme;
+
+/// An iterator over the segmented lines or verses of a file.
+///
+/// Each item is a `(line_number, tokens)` pair, where `line_number` is the 1-based number of the
+/// first line in the unit. Token offsets are char offsets into the unit, whose lines are joined
+/// with `\n`. As in `Chedaka.run_document`, punctuation and verse numbers are not segmented.
+#[pyclass(name = "FileIter")]
+pub struct PyFileIter {
+    chedaka: Py<PyChedaka>,
+    reader: Reader,
+    scheme: PyScheme,
+    by_verse: bool,
+    /// The number of lines read so far.
+    line_number: usize,
+}
+
+#[pymethods]
+impl PyFileIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>) -> PyResult<Option<(usize, Vec<PyToken>)>> {
+        let py = slf.py();
+        let (line_number, text) = match slf.next_unit(py)? {
+            Some(unit) => unit,
+            None => return Ok(None),
+        };
+
+        let chedaka = slf.chedaka.borrow(py);
+        let chars: Vec<char> = text.chars().collect();
+
+        let mut ret = Vec::new();
+        for unit in split_units(&text) {
+            let (start, end) = unit.span;
+            let unit_text: String = chars[start..end].iter().collect();
+            let mut tokens = match chedaka.segment(&unit_text, slf.scheme, slf.scheme) {
+                Ok(tokens) => tokens,
+                Err(e) => return Err(WrappedError(e).into()),
+            };
+            shift(&mut tokens, start);
+            ret.extend(tokens);
+        }
+        Ok(Some((line_number, ret)))
+    }
+}
+
+impl Drop for PyFileIter {
+    /// Closes the Python file, if any, in case iteration stopped before the end of the file.
+    fn drop(&mut self) {
+        if let Reader::Python(file) = &self.reader {
+            Python::with_gil(|py| {
+                // We can't raise an error here, and closing a closed file does nothing.
+                let _ = file.call_method0(py, "close");
+            });
+        }
+    }
+}
+
+/// Reads the lines of a file.
+enum Reader {
+    /// Reads a UTF-8 file directly.
+    Utf8(BufReader<File>),
+    /// Reads a file through a Python text file object, which decodes it for us.
+    Python(PyObject),
+}
+
+impl PyFileIter {
+    /// Opens `path` for reading.
+    ///
+    /// If `encoding` is not a name for UTF-8, we open the file with Python's `open`, which raises
+    /// a `LookupError` if it does not know the encoding.
+    pub(crate) fn new(
+        py: Python,
+        chedaka: Py<PyChedaka>,
+        path: &Path,
+        encoding: &str,
+        scheme: PyScheme,
+        by_verse: bool,
+    ) -> PyResult<Self> {
+        let normalized = encoding.to_ascii_lowercase().replace(['-', '_'], "");
+        let reader = if normalized == "utf8" || normalized == "utf8sig" {
+            let file = File::open(path).map_err(|e| PyOSError::new_err(format!("{}", e)))?;
+            Reader::Utf8(BufReader::new(file))
+        } else {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("encoding", encoding)?;
+            let file = py
+                .import("builtins")?
+                .getattr("open")?
+                .call((path.to_path_buf(),), Some(kwargs))?;
+            Reader::Python(file.into())
+        };
+
+        Ok(Self {
+            chedaka,
+            reader,
+            scheme,
+            by_verse,
+            line_number: 0,
+        })
+    }
+
+    /// Returns the next line without its line ending, or `None` at the end of the file.
+    fn next_line(&mut self, py: Python) -> PyResult<Option<String>> {
+        let buf = match &mut self.reader {
+            Reader::Utf8(reader) => {
+                let mut buf = Vec::new();
+                reader
+                    .read_until(b'\n', &mut buf)
+                    .map_err(|e| PyOSError::new_err(format!("{}", e)))?;
+                buf
+            }
+            Reader::Python(file) => {
+                let line: String = file.call_method0(py, "readline")?.extract(py)?;
+                if line.is_empty() {
+                    file.call_method0(py, "close")?;
+                }
+                line.into_bytes()
+            }
+        };
+        if buf.is_empty() {
+            return Ok(None);
+        }
+        self.line_number += 1;
+
+        let mut line = match String::from_utf8(buf) {
+            Ok(line) => line,
+            Err(_) => {
+                let message = format!("Line {} is not valid UTF-8.", self.line_number);
+                return Err(PyValueError::new_err(message));
+            }
+        };
+        if self.line_number == 1 && line.starts_with('\u{feff}') {
+            line.remove(0);
+        }
+        let len = line.trim_end_matches(['\n', '\r']).len();
+        line.truncate(len);
+        Ok(Some(line))
+    }
+
+    /// Returns the next non-empty unit and the number of its first line.
+    ///
+    /// A unit is a single line, or, if `by_verse` is set, all lines up to and including the next
+    /// line that ends a verse. A blank line also ends a verse.
+    fn next_unit(&mut self, py: Python) -> PyResult<Option<(usize, String)>> {
+        let mut lines: Vec<String> = Vec::new();
+        let mut first_line_number = 0;
+        while let Some(line) = self.next_line(py)? {
+            if line.trim().is_empty() {
+                if lines.is_empty() {
+                    continue;
+                }
+                break;
+            }
+
+            if lines.is_empty() {
+                first_line_number = self.line_number;
+            }
+            let ends_unit = !self.by_verse || has_verse_end(&line);
+            lines.push(line);
+            if ends_unit {
+                break;
+            }
+        }
+
+        if lines.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some((first_line_number, lines.join("\n"))))
+        }
+    }
+}
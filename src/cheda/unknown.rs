@@ -0,0 +1,104 @@
+//! Heuristics for words that are missing from the lexicon.
+use pyo3::prelude::*;
+
+/// A guess at the structure of an unknown word.
+#[pyclass(name = "Guess", get_all)]
+#[derive(Clone, Debug)]
+pub struct PyGuess {
+    /// The likely stem, in SLP1.
+    pub stem: String,
+    /// The likely ending, in SLP1.
+    pub ending: String,
+}
+
+#[pymethods]
+impl PyGuess {
+    fn __repr__(&self) -> String {
+        format!("Guess(stem='{}', ending='{}')", self.stem, self.ending)
+    }
+}
+
+/// Common inflectional endings as (ending, final sound of the stem).
+///
+/// Each ending includes the final sound of the stem as it appears on the surface. For example,
+/// `devena` is `deva` + `ena`, so its row is `("ena", "a")`.
+const ENDINGS: &[(&str, &str)] = &[
+    // Nominals
+    ("AByAm", "a"),
+    ("eByas", "a"),
+    ("AnAm", "a"),
+    ("asya", "a"),
+    ("Ani", "a"),
+    ("Aya", "a"),
+    ("ezu", "a"),
+    ("ena", "a"),
+    ("ayA", "A"),
+    ("AyAs", "A"),
+    ("AyAm", "A"),
+    ("AyE", "A"),
+    ("inA", "i"),
+    ("Inam", "i"),
+    ("ayas", "i"),
+    ("unA", "u"),
+    ("avas", "u"),
+    ("am", "a"),
+    ("as", "a"),
+    ("At", "a"),
+    ("As", "a"),
+    ("Es", "a"),
+    ("O", "a"),
+    ("e", "a"),
+    ("is", "i"),
+    ("us", "u"),
+    // Verbs
+    ("anti", ""),
+    ("ante", ""),
+    ("Amas", ""),
+    ("Avas", ""),
+    ("Ami", ""),
+    ("ati", ""),
+    ("ate", ""),
+    ("asi", ""),
+    ("ase", ""),
+    ("atu", ""),
+    ("atas", ""),
+    ("ti", ""),
+    ("te", ""),
+    ("si", ""),
+    ("mi", ""),
+];
+
+/// The shortest stem we will guess.
+const MIN_STEM_LEN: usize = 2;
+
+/// Guesses the stem and ending of the SLP1 word `text` from the longest common ending it has.
+///
+/// The returned ending is whatever follows the stem in `text`. For example, `devasya` becomes
+/// `deva` + `sya`, and `devena` becomes `deva` + `ena`.
+pub(crate) fn guess(text: &str) -> Option<PyGuess> {
+    // `vidyut` keys use a final `s` rather than a visarga.
+    let text = match text.strip_suffix('H') {
+        Some(head) => format!("{head}s"),
+        None => text.to_string(),
+    };
+
+    let (head, stem_final) = ENDINGS
+        .iter()
+        .filter_map(|(ending, stem_final)| {
+            let head = text.strip_suffix(ending)?;
+            Some((head, *stem_final))
+        })
+        .filter(|(head, stem_final)| head.len() + stem_final.len() >= MIN_STEM_LEN)
+        .min_by_key(|(head, _)| head.len())?;
+
+    let stem = format!("{head}{stem_final}");
+    let shared = stem
+        .bytes()
+        .zip(text.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    Some(PyGuess {
+        ending: text[shared..].to_string(),
+        stem,
+    })
+}
@@ -1,60 +1,241 @@
-use pyo3::exceptions::PyOSError;
+use keys::{Fst, PyKeyIter};
+use pyo3::exceptions::{PyOSError, PyValueError};
 use pyo3::prelude::*;
-use semantics::PyPada;
+use pyo3::types::PyDict;
+use semantics::{PyPada, PyPartOfSpeech};
 use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
 use vidyut_kosha as rust;
 
+mod fuzzy;
+pub mod keys;
+mod lemmas;
+mod pattern;
 pub mod semantics;
 
 /// A compact Sanskrit kosha.
-#[pyclass]
-pub struct Kosha(rust::Kosha);
+///
+/// A `Kosha` can be pickled. Pickling stores only the path it was loaded from, so the files at
+/// that path must still exist when it is unpickled.
+///
+/// A `Kosha` maps some of its files into memory, so don't overwrite them, e.g. with a `Builder`
+/// at the same path, while a `Kosha` that uses them is alive.
+#[pyclass(module = "vidyut.kosha")]
+#[derive(Clone)]
+pub struct Kosha {
+    kosha: Arc<rust::Kosha>,
+    /// The path this kosha was loaded from.
+    path: PathBuf,
+    /// The FST in `kosha`, which we map into memory in `new`.
+    fst: Arc<Fst>,
+    /// The number of keys in `kosha`, which we count on first use.
+    len: OnceLock<usize>,
+    /// The lemma index for `kosha`, if it has one.
+    lemmas: Option<Arc<lemmas::Index>>,
+}
+
+impl Kosha {
+    /// Returns a shared handle to the underlying kosha.
+    pub(crate) fn shared(&self) -> Arc<rust::Kosha> {
+        Arc::clone(&self.kosha)
+    }
+
+    /// Returns a shared handle to the FST in this kosha.
+    fn fst(&self) -> Arc<Fst> {
+        Arc::clone(&self.fst)
+    }
+
+    /// Pairs each key in `keys` with its entries.
+    fn with_entries(&self, keys: Vec<String>) -> Vec<(String, Vec<PyPada>)> {
+        keys.into_iter()
+            .map(|key| {
+                let entries = unpack_all(&self.kosha, &key);
+                (key, entries)
+            })
+            .collect()
+    }
+}
+
+/// Returns all entries for `key` in `kosha`.
+fn unpack_all(kosha: &rust::Kosha, key: &str) -> Vec<PyPada> {
+    kosha
+        .get_all(key)
+        .iter()
+        .filter_map(|p| kosha.unpack(p).ok().map(|pada| pada.into()))
+        .collect()
+}
 
 #[pymethods]
 impl Kosha {
     /// Load a `Kosha` instance from the given input path.
     #[new]
     fn new(path: PathBuf) -> PyResult<Self> {
-        match rust::Kosha::new(&path) {
-            Ok(k) => Ok(Self(k)),
-            Err(_) => Err(PyOSError::new_err(
-                "Unknown error. Our best guess is that the input file is missing.",
-            )),
-        }
+        let kosha = match rust::Kosha::new(&path) {
+            Ok(k) => k,
+            Err(_) => {
+                return Err(PyOSError::new_err(
+                    "Unknown error. Our best guess is that the input file is missing.",
+                ))
+            }
+        };
+        Ok(Self {
+            kosha: Arc::new(kosha),
+            fst: Arc::new(keys::load(&path)?),
+            len: OnceLock::new(),
+            lemmas: lemmas::load(&path)?.map(Arc::new),
+            path,
+        })
+    }
+
+    fn __getnewargs__(&self) -> (PathBuf,) {
+        (self.path.clone(),)
     }
 
     /// Return whether the kosha contains `key`.
     pub fn __contains__(&self, key: String) -> bool {
-        self.0.contains_key(&key)
+        self.kosha.contains_key(&key)
     }
 
     /// Return whether the kosha contains at least one key with prefix `prefix`.
     pub fn contains_prefix(&self, prefix: String) -> bool {
-        self.0.contains_prefix(&prefix)
+        self.kosha.contains_prefix(&prefix)
+    }
+
+    /// Return an iterator over all `(key, entries)` pairs whose key starts with `prefix`, in
+    /// lexicographic order.
+    ///
+    /// If `limit` is set, the iterator stops after producing `limit` pairs.
+    #[pyo3(signature = (prefix, limit = None))]
+    pub fn iter_prefix(&self, prefix: String, limit: Option<usize>) -> PyKeyIter {
+        PyKeyIter::new(self.shared(), self.fst(), &prefix, true, limit)
     }
 
     /// Return all entries with the given `key`.
     pub fn get_all(&self, key: String) -> Vec<PyPada> {
-        let results = self.0.get_all(&key);
-        results
-            .iter()
-            .flat_map(|p| {
-                if let Ok(pada) = self.0.unpack(p) {
-                    Some(pada.into())
-                } else {
-                    None
-                }
+        unpack_all(&self.kosha, &key)
+    }
+
+    /// Return all keys within `max_distance` edits of `key`, as a list of `(key, distance,
+    /// entries)` tuples sorted by distance and then by key.
+    ///
+    /// Insertions, deletions, and most substitutions cost 1. Substitutions between similar SLP1
+    /// sounds cost 0.5: between `s`, `S`, and `z`, and between the short and long forms of a
+    /// vowel, such as `a` and `A`.
+    pub fn search_fuzzy(
+        &self,
+        key: String,
+        max_distance: f64,
+    ) -> PyResult<Vec<(String, f64, Vec<PyPada>)>> {
+        if max_distance.is_nan() || max_distance < 0.0 {
+            return Err(PyValueError::new_err(format!(
+                "max_distance must be non-negative, but got {}",
+                max_distance
+            )));
+        }
+        let ret = fuzzy::search(&self.fst, &key, max_distance)
+            .into_iter()
+            .map(|(key, distance)| {
+                let entries = unpack_all(&self.kosha, &key);
+                (key, distance, entries)
             })
-            .collect()
+            .collect();
+        Ok(ret)
+    }
+
+    /// Return all keys that match the regex `pattern`, as a list of `(key, entries)` pairs in
+    /// lexicographic order.
+    ///
+    /// `pattern` must match the entire key, so `^pra.*ti$` and `pra.*ti` are equivalent. The
+    /// regex syntax is that of the Rust `regex` crate, except that it does not support
+    /// capturing groups or word boundaries.
+    pub fn search_regex(&self, pattern: String) -> PyResult<Vec<(String, Vec<PyPada>)>> {
+        let keys = pattern::search(&self.fst, &pattern)?;
+        Ok(self.with_entries(keys))
+    }
+
+    /// Return all keys that match the glob `pattern`, as a list of `(key, entries)` pairs in
+    /// lexicographic order.
+    ///
+    /// In `pattern`, `*` matches any sequence of characters and `?` matches any single
+    /// character. For example, `*Aya` matches all keys that end in `Aya`.
+    pub fn search_glob(&self, pattern: String) -> PyResult<Vec<(String, Vec<PyPada>)>> {
+        let regex = pattern::glob_to_regex(&pattern);
+        let keys = pattern::search(&self.fst, &regex)?;
+        Ok(self.with_entries(keys))
+    }
+
+    /// Return all forms of `lemma` as a list of `(key, pada)` pairs, sorted by key.
+    ///
+    /// If `pos` is set, return only forms with that part of speech. Other keyword arguments
+    /// restrict the forms by the `Pada` field with the same name. For example, to get all
+    /// present-tense forms of *gam*:
+    ///
+    ///     kosha.forms_of("gam", PartOfSpeech.Tinanta, lakara=Lakara.Lat)
+    ///
+    /// This method uses a lemma index that `Builder.finish` writes. If the kosha has no such
+    /// index, e.g. because it was built without `Builder`, this method raises an `OSError`.
+    #[pyo3(signature = (lemma, pos = None, **filters))]
+    pub fn forms_of(
+        &self,
+        lemma: String,
+        pos: Option<PyPartOfSpeech>,
+        filters: Option<&PyDict>,
+    ) -> PyResult<Vec<(String, PyPada)>> {
+        let filter = lemmas::Filter::new(lemma, pos, filters)?;
+        let index = match &self.lemmas {
+            Some(index) => index,
+            None => return Err(lemmas::missing(&self.path)),
+        };
+        let keys = lemmas::keys_for(index, filter.lemma());
+
+        let mut ret = Vec::new();
+        for key in keys {
+            for pada in unpack_all(&self.kosha, &key) {
+                if filter.matches(&pada) {
+                    ret.push((key.clone(), pada));
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Return an iterator over all keys in the kosha, in lexicographic order.
+    ///
+    /// The iterator produces keys one at a time, so iterating does not create a Python object for
+    /// every key at once. Keys that are not printable ASCII, such as keys with spaces, are skipped.
+    pub fn keys(&self) -> PyKeyIter {
+        PyKeyIter::new(self.shared(), self.fst(), "", false, None)
+    }
+
+    /// Return an iterator over all `(key, entries)` pairs in the kosha, in lexicographic order,
+    /// where `entries` is the same list that `get_all` returns for `key`.
+    pub fn items(&self) -> PyKeyIter {
+        PyKeyIter::new(self.shared(), self.fst(), "", true, None)
+    }
+
+    /// Return an iterator over all keys in the kosha. See `keys`.
+    pub fn __iter__(&self) -> PyKeyIter {
+        self.keys()
+    }
+
+    /// Return the number of distinct keys in the kosha.
+    ///
+    /// The first call counts every key, which takes time proportional to the size of the kosha.
+    pub fn __len__(&self) -> usize {
+        *self.len.get_or_init(|| keys::count(&self.fst))
     }
 }
 
 /// Builder for a `Kosha`.
 ///
-/// Memory usage is linear in the number of unique lemmas.
+/// Memory usage is linear in the number of unique lemmas. The builder also keeps up to 64 MiB of
+/// entries for the lemma index in memory and spills the rest to temporary files in the output
+/// directory, which it removes in `finish`.
 #[pyclass]
 pub struct Builder {
     builder: Option<rust::Builder>,
+    /// Writes the lemma index as entries are inserted.
+    lemmas: lemmas::Writer,
 }
 
 #[pymethods]
@@ -65,7 +246,10 @@ impl Builder {
     #[new]
     fn new(path: PathBuf) -> PyResult<Self> {
         match rust::Builder::new(&path) {
-            Ok(k) => Ok(Self { builder: Some(k) }),
+            Ok(k) => Ok(Self {
+                builder: Some(k),
+                lemmas: lemmas::Writer::new(&path),
+            }),
             Err(_) => Err(PyOSError::new_err(
                 "Unknown error. Our guess is that the input file is missing.",
             )),
@@ -77,9 +261,13 @@ impl Builder {
     /// Keys must be inserted in lexicographic order. If a key is received out of order,
     /// this method will raise an `OSError`.
     fn insert(&mut self, key: String, pada: PyPada) -> PyResult<()> {
+        let lemma = pada.lemma();
         match self.builder {
             Some(ref mut b) => match b.insert(&key, &pada.into()) {
-                Ok(()) => Ok(()),
+                Ok(()) => match lemma {
+                    Some(lemma) => self.lemmas.insert(&lemma, &key),
+                    None => Ok(()),
+                },
                 Err(_) => Err(PyOSError::new_err("Could not write key.")),
             },
             None => Err(PyOSError::new_err("Kosha has already been written.")),
@@ -88,13 +276,14 @@ impl Builder {
 
     /// Complete the build process.
     ///
-    /// If this method is not called, the output data will be invalid.
+    /// This method also writes the lemma index that `Kosha.forms_of` uses. If this method is not
+    /// called, the output data will be invalid.
     fn finish(&mut self) -> PyResult<()> {
         if let Some(x) = self.builder.take() {
             let builder = x;
 
             match builder.finish() {
-                Ok(()) => Ok(()),
+                Ok(()) => self.lemmas.finish(),
                 Err(_) => Err(PyOSError::new_err("Could not write kosha.")),
             }
         } else {
@@ -0,0 +1,126 @@
+//! Finds keys that are close to a query by a weighted edit distance.
+//!
+//! We run a Levenshtein automaton over the kosha's FST. The automaton keeps one row of the usual
+//! edit distance table as its state, which lets us give some edits a lower cost than others.
+use fst::Automaton;
+
+use crate::kosha::keys::{self, Fst};
+
+/// The cost of an ordinary insertion, deletion, or substitution.
+const FULL_COST: u32 = 2;
+
+/// The cost of a substitution between two similar sounds.
+const CHEAP_COST: u32 = 1;
+
+/// Groups of SLP1 sounds that are easily confused with each other, either in speech or in
+/// transcription. A substitution within a group has cost `CHEAP_COST`.
+const SIMILAR: &[&[u8]] = &[b"sSz", b"aA", b"iI", b"uU", b"fF", b"xX"];
+
+/// Returns the cost of substituting `x` with `y`.
+fn substitution_cost(x: u8, y: u8) -> u32 {
+    if x == y {
+        0
+    } else if SIMILAR
+        .iter()
+        .any(|group| group.contains(&x) && group.contains(&y))
+    {
+        CHEAP_COST
+    } else {
+        FULL_COST
+    }
+}
+
+/// Converts a distance from the internal cost units to the units we show to the user.
+fn to_distance(cost: u32) -> f64 {
+    f64::from(cost) / f64::from(FULL_COST)
+}
+
+/// A Levenshtein automaton with SLP1-aware substitution costs.
+///
+/// Costs are stored in half-units so that we can compare them exactly.
+struct Slp1Levenshtein<'a> {
+    query: &'a [u8],
+    max_cost: u32,
+}
+
+impl<'a> Slp1Levenshtein<'a> {
+    fn new(query: &'a str, max_distance: f64) -> Self {
+        Self {
+            query: query.as_bytes(),
+            max_cost: (max_distance * f64::from(FULL_COST)).floor() as u32,
+        }
+    }
+
+    /// Returns the row that follows `row` after reading `byte`.
+    fn step(&self, row: &[u32], byte: u8) -> Vec<u32> {
+        let mut next = Vec::with_capacity(row.len());
+        next.push(row[0] + FULL_COST);
+        for (j, q) in self.query.iter().enumerate() {
+            let cost = (row[j + 1] + FULL_COST)
+                .min(next[j] + FULL_COST)
+                .min(row[j] + substitution_cost(*q, byte));
+            next.push(cost);
+        }
+        next
+    }
+
+    /// Returns the distance between the query and `key`.
+    fn distance(&self, key: &[u8]) -> u32 {
+        let mut row = self.start_row();
+        for byte in key {
+            row = self.step(&row, *byte);
+        }
+        row[self.query.len()]
+    }
+
+    fn start_row(&self) -> Vec<u32> {
+        (0..=self.query.len() as u32)
+            .map(|j| j * FULL_COST)
+            .collect()
+    }
+}
+
+impl Automaton for Slp1Levenshtein<'_> {
+    /// The last row of the edit distance table, or `None` if no match is possible.
+    type State = Option<Vec<u32>>;
+
+    fn start(&self) -> Self::State {
+        Some(self.start_row())
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        state
+            .as_ref()
+            .is_some_and(|row| row[self.query.len()] <= self.max_cost)
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        let row = state.as_ref()?;
+        let next = self.step(row, byte);
+        if next.iter().any(|c| *c <= self.max_cost) {
+            Some(next)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns all keys in `fst` within `max_distance` of `query`, along with their distances.
+///
+/// Results are sorted by distance, then by key.
+pub(crate) fn search(fst: &Fst, query: &str, max_distance: f64) -> Vec<(String, f64)> {
+    let aut = Slp1Levenshtein::new(query, max_distance);
+    let mut ret: Vec<_> = keys::search(fst, &aut)
+        .into_iter()
+        .map(|key| (aut.distance(key.as_bytes()), key))
+        .collect();
+
+    ret.sort();
+    ret.into_iter()
+        .map(|(cost, key)| (key, to_distance(cost)))
+        .collect()
+}
@@ -0,0 +1,165 @@
+//! Iterates over the keys in a kosha.
+//!
+//! `vidyut_kosha::Kosha` supports exact lookups only, so we map its FST into memory ourselves. The
+//! FST stores a key with several entries once as-is and once more for each extra entry, with some
+//! non-printing marker bytes appended. When we iterate, we skip the marked copies and use
+//! `Kosha::get_all` to fetch every entry for a key.
+use fst::{Automaton, IntoStreamer, Streamer};
+use memmap2::Mmap;
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use vidyut_kosha as rust;
+
+use crate::kosha::unpack_all;
+
+/// The FST inside a kosha, mapped into memory.
+pub(crate) type Fst = fst::Map<Mmap>;
+
+/// Maps the FST for the kosha at `path` into memory.
+///
+/// `vidyut_kosha::Kosha` does not expose its own copy of the FST. We map the file instead of
+/// reading it so that our copy shares pages with the OS cache and does not double the FST's
+/// memory.
+pub(crate) fn load(path: &Path) -> PyResult<Fst> {
+    let fst_path = path.join("padas.fst");
+    let to_err = |message: String| {
+        let message = format!("Could not read '{}': {}", fst_path.display(), message);
+        PyOSError::new_err(message)
+    };
+
+    let file = File::open(&fst_path).map_err(|e| to_err(e.to_string()))?;
+    // SAFETY: the map is valid as long as no one modifies the file while it is mapped. We
+    // document this restriction on `Kosha`.
+    let mmap = unsafe { Mmap::map(&file) }.map_err(|e| to_err(e.to_string()))?;
+    fst::Map::new(mmap).map_err(|e| to_err(e.to_string()))
+}
+
+/// Returns whether `key` is a key as inserted, rather than a copy with a duplicate marker.
+///
+/// `vidyut_kosha` does not expose its marker bytes, so we assume that every key as inserted is
+/// SLP1 text, which uses only printable ASCII without spaces. Any key with some other byte, such
+/// as a space or a non-ASCII character, is treated as a marked copy and skipped.
+pub(crate) fn is_base_key(key: &[u8]) -> bool {
+    key.iter().all(|b| b.is_ascii_graphic())
+}
+
+/// Returns `key` as a string if it is a base key, or `None` if it is a marked copy.
+fn to_base_key(key: &[u8]) -> Option<String> {
+    if is_base_key(key) {
+        // Base keys are ASCII, so this conversion always succeeds.
+        String::from_utf8(key.to_vec()).ok()
+    } else {
+        None
+    }
+}
+
+/// Returns the number of distinct keys in `fst`.
+pub(crate) fn count(fst: &Fst) -> usize {
+    let mut stream = fst.keys();
+    let mut ret = 0;
+    while let Some(key) = stream.next() {
+        if is_base_key(key) {
+            ret += 1;
+        }
+    }
+    ret
+}
+
+/// Returns the keys in `fst` that `aut` matches, in lexicographic order.
+///
+/// Searching with an automaton visits only the parts of the FST that can still produce a match.
+pub(crate) fn search<A: Automaton>(fst: &Fst, aut: A) -> Vec<String> {
+    let mut ret = Vec::new();
+    let mut stream = fst.search(aut).into_stream();
+    while let Some((key, _)) = stream.next() {
+        ret.extend(to_base_key(key));
+    }
+    ret
+}
+
+/// An iterator over the keys in a `Kosha`, in lexicographic order.
+///
+/// Depending on how it was created, this iterator yields either keys or `(key, entries)` pairs.
+#[pyclass(name = "KeyIter")]
+pub struct PyKeyIter {
+    kosha: Arc<rust::Kosha>,
+    fst: Arc<Fst>,
+    /// Only yield keys that start with this prefix.
+    prefix: Vec<u8>,
+    /// The last key we read from `fst`, if any.
+    last: Option<Vec<u8>>,
+    /// Whether to yield `(key, entries)` pairs instead of keys.
+    with_entries: bool,
+    /// The maximum number of items to yield.
+    limit: Option<usize>,
+    /// The number of items yielded so far.
+    num_yielded: usize,
+}
+
+impl PyKeyIter {
+    pub(crate) fn new(
+        kosha: Arc<rust::Kosha>,
+        fst: Arc<Fst>,
+        prefix: &str,
+        with_entries: bool,
+        limit: Option<usize>,
+    ) -> Self {
+        Self {
+            kosha,
+            fst,
+            prefix: prefix.as_bytes().to_vec(),
+            last: None,
+            with_entries,
+            limit,
+            num_yielded: 0,
+        }
+    }
+
+    /// Returns the next key with `prefix`, or `None` if there are no more.
+    ///
+    /// We start a new range query for each key so that we don't have to hold a stream that
+    /// borrows from `fst`.
+    fn next_key(&mut self) -> Option<String> {
+        loop {
+            let builder = match &self.last {
+                Some(last) => self.fst.range().gt(last),
+                None => self.fst.range().ge(&self.prefix),
+            };
+            let mut stream = builder.into_stream();
+            let (key, _) = stream.next()?;
+            if !key.starts_with(&self.prefix) {
+                return None;
+            }
+            self.last = Some(key.to_vec());
+
+            if let Some(key) = to_base_key(key) {
+                return Some(key);
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl PyKeyIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> Option<PyObject> {
+        if self.limit.is_some_and(|n| self.num_yielded >= n) {
+            return None;
+        }
+        let key = self.next_key()?;
+        self.num_yielded += 1;
+
+        if self.with_entries {
+            let entries = unpack_all(&self.kosha, &key);
+            Some((key, entries).into_py(py))
+        } else {
+            Some(key.into_py(py))
+        }
+    }
+}
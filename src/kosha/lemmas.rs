@@ -0,0 +1,325 @@
+//! Maps lemmas to the keys whose entries have that lemma.
+//!
+//! `vidyut_kosha::Kosha` maps keys to entries but not the other way around, so `Builder` writes a
+//! second FST next to the kosha's own files. This FST is a set that contains `lemma + "\0" + key`
+//! for every entry, so the keys for a lemma are the members that start with `lemma + "\0"`.
+use fst::{IntoStreamer, SetBuilder, Streamer};
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::kosha::semantics::{
+    PyLakara, PyLinga, PyPada, PyPadaPrayoga, PyPartOfSpeech, PyPurusha, PyVacana, PyVibhakti,
+};
+
+/// The lemma index for a kosha.
+pub(crate) type Index = fst::Set<Vec<u8>>;
+
+/// The name of the index file within the kosha's directory.
+const FILE_NAME: &str = "lemmas.fst";
+
+/// Separates the lemma from the key in each member of the index.
+const SEP: u8 = 0;
+
+/// The most bytes of index entries that `Writer` holds in memory at once.
+const CHUNK_BYTES: usize = 64 << 20;
+
+/// Returns the index member for the given `lemma` and `key`.
+fn entry(lemma: &str, key: &str) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(lemma.len() + key.len() + 1);
+    ret.extend(lemma.as_bytes());
+    ret.push(SEP);
+    ret.extend(key.as_bytes());
+    ret
+}
+
+/// Returns an `OSError` for a failed read from `path`.
+fn read_error(path: &Path, e: impl std::fmt::Display) -> PyErr {
+    let message = format!("Could not read '{}': {}", path.display(), e);
+    PyOSError::new_err(message)
+}
+
+/// Returns an `OSError` for a failed write to `path`.
+fn write_error(path: &Path, e: impl std::fmt::Display) -> PyErr {
+    let message = format!("Could not write '{}': {}", path.display(), e);
+    PyOSError::new_err(message)
+}
+
+/// Writes the index for a kosha as its entries are inserted.
+///
+/// Entries arrive in key order, but the index is sorted by lemma. So we sort entries in chunks of
+/// about `CHUNK_BYTES`, write each sorted chunk to a temporary file in the kosha's directory, and
+/// merge these files in `finish`. Memory usage is then bounded by the chunk size rather than by
+/// the number of entries.
+pub(crate) struct Writer {
+    /// The kosha's directory.
+    path: PathBuf,
+    /// Entries that we have not yet written to a chunk file.
+    chunk: Vec<Vec<u8>>,
+    /// The total length of the entries in `chunk`.
+    chunk_bytes: usize,
+    /// The chunk files written so far, each sorted and without duplicates.
+    chunk_paths: Vec<PathBuf>,
+}
+
+impl Writer {
+    /// Creates a writer for the kosha at `path`.
+    pub(crate) fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            chunk: Vec::new(),
+            chunk_bytes: 0,
+            chunk_paths: Vec::new(),
+        }
+    }
+
+    /// Adds an entry for the given `lemma` and `key`.
+    pub(crate) fn insert(&mut self, lemma: &str, key: &str) -> PyResult<()> {
+        let entry = entry(lemma, key);
+        self.chunk_bytes += entry.len();
+        self.chunk.push(entry);
+        if self.chunk_bytes >= CHUNK_BYTES {
+            self.write_chunk()?;
+        }
+        Ok(())
+    }
+
+    /// Sorts the current chunk and writes it to a new chunk file.
+    ///
+    /// Each entry in the file is its length as a little-endian `u32`, followed by its bytes.
+    fn write_chunk(&mut self) -> PyResult<()> {
+        let mut chunk = std::mem::take(&mut self.chunk);
+        self.chunk_bytes = 0;
+        chunk.sort();
+        chunk.dedup();
+
+        let chunk_path = self
+            .path
+            .join(format!("{}.{}.tmp", FILE_NAME, self.chunk_paths.len()));
+        let file = File::create(&chunk_path).map_err(|e| write_error(&chunk_path, e))?;
+        self.chunk_paths.push(chunk_path.clone());
+
+        let mut writer = BufWriter::new(file);
+        for entry in &chunk {
+            let len = entry.len() as u32;
+            writer
+                .write_all(&len.to_le_bytes())
+                .and_then(|_| writer.write_all(entry))
+                .map_err(|e| write_error(&chunk_path, e))?;
+        }
+        writer.flush().map_err(|e| write_error(&chunk_path, e))
+    }
+
+    /// Writes the index to the kosha's directory and removes the chunk files.
+    pub(crate) fn finish(&mut self) -> PyResult<()> {
+        let index_path = self.path.join(FILE_NAME);
+        let file = File::create(&index_path).map_err(|e| write_error(&index_path, e))?;
+        let mut builder =
+            SetBuilder::new(BufWriter::new(file)).map_err(|e| write_error(&index_path, e))?;
+
+        if self.chunk_paths.is_empty() {
+            // Everything fits in one chunk, so we can skip the chunk files.
+            let mut chunk = std::mem::take(&mut self.chunk);
+            chunk.sort();
+            chunk.dedup();
+            for entry in &chunk {
+                builder
+                    .insert(entry)
+                    .map_err(|e| write_error(&index_path, e))?;
+            }
+        } else {
+            if !self.chunk.is_empty() {
+                self.write_chunk()?;
+            }
+            self.merge_chunks(|entry| {
+                builder
+                    .insert(entry)
+                    .map_err(|e| write_error(&index_path, e))
+            })?;
+        }
+        builder.finish().map_err(|e| write_error(&index_path, e))?;
+        self.remove_chunks();
+        Ok(())
+    }
+
+    /// Calls `f` on each distinct entry in the chunk files, in sorted order.
+    fn merge_chunks(&self, mut f: impl FnMut(&[u8]) -> PyResult<()>) -> PyResult<()> {
+        let mut readers = Vec::new();
+        for chunk_path in &self.chunk_paths {
+            let file = File::open(chunk_path).map_err(|e| read_error(chunk_path, e))?;
+            readers.push(BufReader::new(file));
+        }
+
+        // A min-heap of the next entry in each chunk file.
+        let mut heap = BinaryHeap::new();
+        for (i, reader) in readers.iter_mut().enumerate() {
+            if let Some(entry) =
+                read_entry(reader).map_err(|e| read_error(&self.chunk_paths[i], e))?
+            {
+                heap.push(Reverse((entry, i)));
+            }
+        }
+
+        let mut last: Option<Vec<u8>> = None;
+        while let Some(Reverse((entry, i))) = heap.pop() {
+            if last.as_ref() != Some(&entry) {
+                f(&entry)?;
+            }
+            if let Some(next) =
+                read_entry(&mut readers[i]).map_err(|e| read_error(&self.chunk_paths[i], e))?
+            {
+                heap.push(Reverse((next, i)));
+            }
+            last = Some(entry);
+        }
+        Ok(())
+    }
+
+    /// Removes any chunk files that we have written.
+    fn remove_chunks(&mut self) {
+        for chunk_path in self.chunk_paths.drain(..) {
+            // A leftover file is harmless, so we ignore errors here.
+            let _ = fs::remove_file(chunk_path);
+        }
+    }
+}
+
+impl Drop for Writer {
+    /// Removes the chunk files, in case `finish` was never called or failed.
+    fn drop(&mut self) {
+        self.remove_chunks();
+    }
+}
+
+/// Reads the next entry from a chunk file, or returns `None` at the end of the file.
+fn read_entry(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut entry = vec![0; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut entry)?;
+    Ok(Some(entry))
+}
+
+/// Reads the index for the kosha at `path`, or returns `None` if the kosha has no index.
+pub(crate) fn load(path: &Path) -> PyResult<Option<Index>> {
+    let index_path = path.join(FILE_NAME);
+    let bytes = match fs::read(&index_path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(read_error(&index_path, e)),
+    };
+    match fst::Set::new(bytes) {
+        Ok(set) => Ok(Some(set)),
+        Err(e) => Err(read_error(&index_path, e)),
+    }
+}
+
+/// Returns the error for a kosha at `path` that has no index.
+pub(crate) fn missing(path: &Path) -> PyErr {
+    let message = format!(
+        "The kosha at '{}' has no lemma index ('{}'), so `forms_of` can't be used. Only \
+         `vidyut.kosha.Builder` writes this index, so a kosha that was built elsewhere, such as \
+         the prebuilt data for `vidyut`, or by an older version of `Builder` lacks it. To use \
+         `forms_of`, build the kosha again with `vidyut.kosha.Builder`.",
+        path.display(),
+        FILE_NAME,
+    );
+    PyOSError::new_err(message)
+}
+
+/// Returns all keys that have an entry with `lemma`, in lexicographic order.
+pub(crate) fn keys_for(index: &Index, lemma: &str) -> Vec<String> {
+    let prefix = entry(lemma, "");
+    let mut ret = Vec::new();
+    let mut stream = index.range().ge(&prefix).into_stream();
+    while let Some(member) = stream.next() {
+        match member.strip_prefix(prefix.as_slice()) {
+            Some(key) => {
+                if let Ok(key) = String::from_utf8(key.to_vec()) {
+                    ret.push(key);
+                }
+            }
+            None => break,
+        }
+    }
+    ret
+}
+
+/// Constraints on the entries that `Kosha.forms_of` returns.
+#[derive(Default)]
+pub(crate) struct Filter {
+    lemma: String,
+    pos: Option<PyPartOfSpeech>,
+    purusha: Option<PyPurusha>,
+    lakara: Option<PyLakara>,
+    pada_prayoga: Option<PyPadaPrayoga>,
+    vacana: Option<PyVacana>,
+    linga: Option<PyLinga>,
+    vibhakti: Option<PyVibhakti>,
+    is_purvapada: Option<bool>,
+}
+
+impl Filter {
+    /// Creates a filter from the given arguments.
+    ///
+    /// Each key in `filters` must be the name of a `Pada` field other than `pos`, `dhatu`, and
+    /// `pratipadika`.
+    pub(crate) fn new(
+        lemma: String,
+        pos: Option<PyPartOfSpeech>,
+        filters: Option<&PyDict>,
+    ) -> PyResult<Self> {
+        let mut ret = Self {
+            lemma,
+            pos,
+            ..Default::default()
+        };
+        for (name, value) in filters.into_iter().flatten() {
+            match name.extract::<&str>()? {
+                "purusha" => ret.purusha = Some(value.extract()?),
+                "lakara" => ret.lakara = Some(value.extract()?),
+                "pada_prayoga" => ret.pada_prayoga = Some(value.extract()?),
+                "vacana" => ret.vacana = Some(value.extract()?),
+                "linga" => ret.linga = Some(value.extract()?),
+                "vibhakti" => ret.vibhakti = Some(value.extract()?),
+                "is_purvapada" => ret.is_purvapada = Some(value.extract()?),
+                other => {
+                    let message = format!("Unknown filter '{}'", other);
+                    return Err(PyValueError::new_err(message));
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Returns the lemma to search for.
+    pub(crate) fn lemma(&self) -> &str {
+        &self.lemma
+    }
+
+    /// Returns whether `pada` satisfies every constraint in this filter.
+    pub(crate) fn matches(&self, pada: &PyPada) -> bool {
+        fn check<T: PartialEq>(expected: &Option<T>, actual: Option<&T>) -> bool {
+            expected.is_none() || expected.as_ref() == actual
+        }
+
+        pada.lemma().as_deref() == Some(self.lemma.as_str())
+            && check(&self.pos, pada.pos())
+            && check(&self.purusha, pada.purusha())
+            && check(&self.lakara, pada.lakara())
+            && check(&self.pada_prayoga, pada.pada_prayoga())
+            && check(&self.vacana, pada.vacana())
+            && check(&self.linga, pada.linga())
+            && check(&self.vibhakti, pada.vibhakti())
+            && check(&self.is_purvapada, Some(&pada.is_purvapada()))
+    }
+}
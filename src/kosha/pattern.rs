@@ -0,0 +1,59 @@
+//! Finds keys that match a regex or glob pattern.
+//!
+//! We compile the pattern to a DFA and run it over the kosha's FST with `keys::search`.
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use regex_automata::dense;
+
+use crate::kosha::keys::{self, Fst};
+
+/// Returns `pattern` without a leading `^` or a trailing `$`.
+///
+/// A pattern must always match an entire key, so these anchors are redundant.
+fn strip_anchors(pattern: &str) -> &str {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    match pattern.strip_suffix('$') {
+        Some(p) if !p.ends_with('\\') => p,
+        _ => pattern,
+    }
+}
+
+/// Converts the glob `pattern` to an equivalent regex.
+///
+/// `*` matches any sequence of characters, and `?` matches any single character. All other
+/// characters match themselves.
+pub(crate) fn glob_to_regex(pattern: &str) -> String {
+    let mut ret = String::new();
+    for c in pattern.chars() {
+        match c {
+            '*' => ret.push_str(".*"),
+            '?' => ret.push('.'),
+            _ => {
+                if "\\.+*?()|[]{}^$#&-~".contains(c) {
+                    ret.push('\\');
+                }
+                ret.push(c);
+            }
+        }
+    }
+    ret
+}
+
+/// Returns all keys in `fst` that fully match the regex `pattern`, in lexicographic order.
+pub(crate) fn search(fst: &Fst, pattern: &str) -> PyResult<Vec<String>> {
+    // Without `longest_match`, the DFA stops at the first match it finds, so `a|ab` would never
+    // match `ab` and `.*?` would match as little as possible.
+    let dfa = match dense::Builder::new()
+        .anchored(true)
+        .longest_match(true)
+        .build(strip_anchors(pattern))
+    {
+        Ok(dfa) => dfa,
+        Err(e) => {
+            let message = format!("Invalid pattern '{}': {}", pattern, e);
+            return Err(PyValueError::new_err(message));
+        }
+    };
+
+    Ok(keys::search(fst, &dfa))
+}
@@ -1,5 +1,7 @@
 use pyo3::basic::CompareOp;
 use pyo3::prelude::*;
+use serde_json::json;
+use std::fmt::Debug;
 
 use vidyut_kosha::semantics::*;
 
@@ -497,14 +499,17 @@ impl PyPada {
     }
 
     /// Create a new *subanta* (nominal).
+    ///
+    /// Set `is_purvapada` for a word that must not occur as the last member of a compound.
     #[staticmethod]
-    #[pyo3(signature=(*, pratipadika, linga, vibhakti, vacana))]
+    #[pyo3(signature=(*, pratipadika, linga, vibhakti, vacana, is_purvapada=false))]
     #[allow(clippy::too_many_arguments)]
     fn make_subanta(
         pratipadika: Option<PyPratipadika>,
         linga: Option<PyLinga>,
         vibhakti: Option<PyVibhakti>,
         vacana: Option<PyVacana>,
+        is_purvapada: bool,
     ) -> Self {
         Self {
             pos: Some(PyPartOfSpeech::Subanta),
@@ -512,6 +517,7 @@ impl PyPada {
             vacana,
             vibhakti,
             linga,
+            is_purvapada,
             ..Default::default()
         }
     }
@@ -615,6 +621,84 @@ impl PyPada {
     }
 }
 
+impl PyPada {
+    /// Returns whether this pada has no analysis.
+    pub(crate) fn is_unknown(&self) -> bool {
+        self.pos.is_none()
+    }
+
+    /// Returns the part of speech of this pada, if known.
+    pub(crate) fn pos(&self) -> Option<&PyPartOfSpeech> {
+        self.pos.as_ref()
+    }
+
+    /// Returns the number of this pada, if known.
+    pub(crate) fn vacana(&self) -> Option<&PyVacana> {
+        self.vacana.as_ref()
+    }
+
+    /// Returns the person of this pada, if known.
+    pub(crate) fn purusha(&self) -> Option<&PyPurusha> {
+        self.purusha.as_ref()
+    }
+
+    /// Returns the tense-mood of this pada, if known.
+    pub(crate) fn lakara(&self) -> Option<&PyLakara> {
+        self.lakara.as_ref()
+    }
+
+    /// Returns the voice of this pada, if known.
+    pub(crate) fn pada_prayoga(&self) -> Option<&PyPadaPrayoga> {
+        self.pada_prayoga.as_ref()
+    }
+
+    /// Returns the gender of this pada, if known.
+    pub(crate) fn linga(&self) -> Option<&PyLinga> {
+        self.linga.as_ref()
+    }
+
+    /// Returns the case of this pada, if known.
+    pub(crate) fn vibhakti(&self) -> Option<&PyVibhakti> {
+        self.vibhakti.as_ref()
+    }
+
+    /// Returns whether this pada must not occur as the last member of a compound.
+    pub(crate) fn is_purvapada(&self) -> bool {
+        self.is_purvapada
+    }
+
+    /// Returns this pada as a JSON object. Enum values use their variant names.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        fn name<T: Debug>(x: &Option<T>) -> Option<String> {
+            x.as_ref().map(|x| format!("{:?}", x))
+        }
+
+        json!({
+            "pos": name(&self.pos),
+            "lemma": self.lemma(),
+            "purusha": name(&self.purusha),
+            "lakara": name(&self.lakara),
+            "pada_prayoga": name(&self.pada_prayoga),
+            "vacana": name(&self.vacana),
+            "linga": name(&self.linga),
+            "vibhakti": name(&self.vibhakti),
+            "is_purvapada": self.is_purvapada,
+        })
+    }
+}
+
+/// Returns the lemma of `pada`, if it has one.
+///
+/// This matches `PyPada::lemma` but doesn't require converting `pada` first.
+pub(crate) fn lemma_of(pada: &Pada) -> Option<&str> {
+    match pada {
+        Pada::Avyaya(a) => Some(a.pratipadika.lemma()),
+        Pada::Subanta(s) => Some(s.pratipadika.lemma()),
+        Pada::Tinanta(t) => Some(t.dhatu.text()),
+        Pada::None => None,
+    }
+}
+
 impl From<Pada> for PyPada {
     fn from(val: Pada) -> Self {
         let mut res = PyPada::default();
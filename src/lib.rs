@@ -10,7 +10,8 @@ Structure
 ---------
 
 The `vidyut` function below defines our native module. It uses symbols from the `cheda`, `kosha`,
-and `prakriya` Rust modules, which define bindings for their corresponding Rust crates.
+`lipi`, `prakriya`, and `sandhi` Rust modules, which define bindings for their corresponding Rust
+crates.
 
 To handle name collisions, we create submodules with PyO3's `wrap_pymodule!` macro. One restriction
 of these native submodules is that we cannot `import` from them:
@@ -61,10 +62,11 @@ the normal Rust style ("Returns" vs. "Return").
 #![warn(clippy::unwrap_used)]
 
 use pyo3::prelude::*;
-use pyo3::wrap_pymodule;
+use pyo3::{wrap_pyfunction, wrap_pymodule};
 
 mod cheda;
 mod kosha;
+mod lipi;
 mod prakriya;
 mod sandhi;
 
@@ -73,9 +75,28 @@ mod sandhi;
 /// For usage examples, see `vidyut/cheda.py`.
 #[pymodule]
 #[pyo3(name = "cheda")]
-fn py_cheda(_py: Python, m: &PyModule) -> PyResult<()> {
+fn py_cheda(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<cheda::PyChedaka>()?;
+    m.add_class::<cheda::boundary::PyBoundary>()?;
+    m.add_class::<cheda::boundary::PySandhiRule>()?;
+    m.add_class::<cheda::compound::PyCompound>()?;
+    m.add_class::<cheda::compound::PySamasaType>()?;
+    m.add_class::<cheda::constraints::PyConstraints>()?;
+    m.add_class::<cheda::evaluate::PyReport>()?;
+    m.add_class::<cheda::evaluate::PyScore>()?;
+    m.add_class::<cheda::evaluate::PySentenceErrors>()?;
+    m.add_class::<cheda::stream::PyFileIter>()?;
+    m.add_class::<cheda::unknown::PyGuess>()?;
+    m.add_class::<cheda::lattice::PyLattice>()?;
+    m.add_class::<cheda::lattice::PyLatticeEdge>()?;
+    m.add_class::<cheda::lattice::PyLatticeNode>()?;
+    m.add_class::<cheda::PySentence>()?;
     m.add_class::<cheda::PyToken>()?;
+    m.add("UnknownWordError", py.get_type::<cheda::UnknownWordError>())?;
+    m.add_function(wrap_pyfunction!(cheda::compound::py_group_compounds, m)?)?;
+    m.add_function(wrap_pyfunction!(cheda::conllu::py_to_conllu, m)?)?;
+    m.add_function(wrap_pyfunction!(cheda::conllu::py_to_json, m)?)?;
+    m.add_function(wrap_pyfunction!(cheda::evaluate::py_evaluate, m)?)?;
 
     Ok(())
 }
@@ -88,6 +109,7 @@ fn py_cheda(_py: Python, m: &PyModule) -> PyResult<()> {
 fn py_kosha(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<kosha::Kosha>()?;
     m.add_class::<kosha::Builder>()?;
+    m.add_class::<kosha::keys::PyKeyIter>()?;
 
     // These symbols have name collisions with their similarly named counterparts in the `prakriya`
     // module. By wrapping these symbols in their own native module, we avoid the name collision
@@ -111,6 +133,18 @@ fn py_kosha(_py: Python, m: &PyModule) -> PyResult<()> {
     Ok(())
 }
 
+/// Defines the `vidyut.lipi` native module.
+///
+/// For usage examples, see `vidyut/lipi.py`.
+#[pymodule]
+#[pyo3(name = "lipi")]
+fn py_lipi(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<lipi::PyScheme>()?;
+    m.add_function(wrap_pyfunction!(lipi::py_transliterate, m)?)?;
+
+    Ok(())
+}
+
 /// Defines the `vidyut.prakriya` native module.
 ///
 /// For usage examples, see `vidyut/prakriya.py`.
@@ -157,6 +191,7 @@ fn py_sandhi(_py: Python, m: &PyModule) -> PyResult<()> {
 fn vidyut(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_wrapped(wrap_pymodule!(py_cheda))?;
     m.add_wrapped(wrap_pymodule!(py_kosha))?;
+    m.add_wrapped(wrap_pymodule!(py_lipi))?;
     m.add_wrapped(wrap_pymodule!(py_prakriya))?;
     m.add_wrapped(wrap_pymodule!(py_sandhi))?;
 
@@ -0,0 +1,48 @@
+//! Defines Python bindings for `vidyut_lipi`.
+use pyo3::prelude::*;
+use vidyut_lipi::{transliterate, Scheme};
+
+/// A transliteration scheme.
+#[pyclass(name = "Scheme", module = "lipi")]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PyScheme {
+    /// The Devanagari script.
+    Devanagari,
+    /// The Harvard-Kyoto transliteration scheme.
+    HarvardKyoto,
+    /// The International Alphabet of Sanskrit Transliteration.
+    Iast,
+    /// The ITRANS transliteration scheme.
+    Itrans,
+    /// The Sanskrit Library Phonetic Basic encoding, which all other Vidyut modules use.
+    Slp1,
+}
+
+impl From<PyScheme> for Scheme {
+    fn from(val: PyScheme) -> Self {
+        use PyScheme::*;
+        match val {
+            Devanagari => Self::Devanagari,
+            HarvardKyoto => Self::HarvardKyoto,
+            Iast => Self::Iast,
+            Itrans => Self::Itrans,
+            Slp1 => Self::Slp1,
+        }
+    }
+}
+
+/// Transliterate `text` from scheme `source` to scheme `dest`.
+#[pyfunction]
+#[pyo3(name = "transliterate", signature = (text, *, source, dest))]
+pub fn py_transliterate(text: &str, source: PyScheme, dest: PyScheme) -> String {
+    to_scheme(text, source, dest)
+}
+
+/// Transliterates `text` from `source` to `dest`, skipping the work if the two are the same.
+pub(crate) fn to_scheme(text: &str, source: PyScheme, dest: PyScheme) -> String {
+    if source == dest {
+        text.to_string()
+    } else {
+        transliterate(text, source.into(), dest.into())
+    }
+}
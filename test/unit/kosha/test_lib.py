@@ -1,4 +1,6 @@
+import pickle
 import tempfile
+from pathlib import Path
 
 import pytest
 
@@ -15,6 +17,7 @@ from vidyut.kosha import (
     Linga,
     Vibhakti,
     Dhatu,
+    PartOfSpeech,
 )
 
 
@@ -55,6 +58,203 @@ def test_init_fails_if_file_does_not_exist():
             kosha = Kosha(tempdir)
 
 
+@pytest.fixture(scope="session")
+def small_kosha():
+    """Create a sample Kosha with a few keys."""
+    with tempfile.TemporaryDirectory() as tempdir:
+        b = Builder(tempdir)
+        for key in ["Bavati", "gacCanti", "gacCati", "gacCati"]:
+            b.insert(key, Pada.make_avyaya(pratipadika=Pratipadika(text=key)))
+        b.finish()
+
+        return Kosha(tempdir)
+
+
+def test_keys(small_kosha):
+    assert list(small_kosha.keys()) == ["Bavati", "gacCanti", "gacCati"]
+    assert list(small_kosha) == ["Bavati", "gacCanti", "gacCati"]
+
+
+def test_items(small_kosha):
+    items = list(small_kosha.items())
+
+    assert [k for k, _ in items] == ["Bavati", "gacCanti", "gacCati"]
+    assert [len(v) for _, v in items] == [1, 1, 2]
+    assert items[0][1][0].lemma == "Bavati"
+
+
+def test_len(small_kosha):
+    assert len(small_kosha) == 3
+
+
+def test_iter_prefix(small_kosha):
+    items = list(small_kosha.iter_prefix("gacC"))
+    assert [k for k, _ in items] == ["gacCanti", "gacCati"]
+    assert [len(v) for _, v in items] == [1, 2]
+
+    assert list(small_kosha.iter_prefix("gacCati")) == items[1:]
+    assert list(small_kosha.iter_prefix("x")) == []
+
+
+def test_iter_prefix_with_limit(small_kosha):
+    items = list(small_kosha.iter_prefix("", limit=2))
+    assert [k for k, _ in items] == ["Bavati", "gacCanti"]
+
+    assert list(small_kosha.iter_prefix("gacC", limit=0)) == []
+
+
+def test_search_fuzzy(small_kosha):
+    assert small_kosha.search_fuzzy("gacCati", 0) == [
+        ("gacCati", 0.0, small_kosha.get_all("gacCati"))
+    ]
+
+    results = small_kosha.search_fuzzy("gacCati", 1)
+    assert [(k, d) for k, d, _ in results] == [("gacCati", 0.0), ("gacCanti", 1.0)]
+
+
+def test_search_fuzzy_with_cheap_substitutions(small_kosha):
+    # Short and long vowels
+    results = small_kosha.search_fuzzy("gacCAtI", 1)
+    assert [(k, d) for k, d, _ in results] == [("gacCati", 1.0)]
+
+    results = small_kosha.search_fuzzy("gacCatI", 0.5)
+    assert [(k, d) for k, d, _ in results] == [("gacCati", 0.5)]
+
+    # Sibilants
+    with tempfile.TemporaryDirectory() as tempdir:
+        b = Builder(tempdir)
+        b.insert("vizaya", Pada.make_avyaya(pratipadika=Pratipadika(text="vizaya")))
+        b.finish()
+        kosha = Kosha(tempdir)
+
+        results = kosha.search_fuzzy("viSaya", 0.5)
+        assert [(k, d) for k, d, _ in results] == [("vizaya", 0.5)]
+        assert kosha.search_fuzzy("vitaya", 0.5) == []
+
+
+def test_search_fuzzy_fails_on_negative_distance(small_kosha):
+    with pytest.raises(ValueError):
+        small_kosha.search_fuzzy("gacCati", -1)
+
+
+def test_search_regex(small_kosha):
+    results = small_kosha.search_regex("gacCa.*ti")
+    assert [k for k, _ in results] == ["gacCanti", "gacCati"]
+    assert results[1][1] == small_kosha.get_all("gacCati")
+
+    results = small_kosha.search_regex("^gacCa(n)?ti$")
+    assert [k for k, _ in results] == ["gacCanti", "gacCati"]
+
+    # Patterns match the entire key.
+    assert small_kosha.search_regex("gacCa") == []
+    assert small_kosha.search_regex("ati") == []
+
+    # Alternations and lazy quantifiers still match the entire key.
+    results = small_kosha.search_regex("gacCa|gacCati")
+    assert [k for k, _ in results] == ["gacCati"]
+
+    results = small_kosha.search_regex("gacC.*?ti")
+    assert [k for k, _ in results] == ["gacCanti", "gacCati"]
+
+
+def test_search_regex_fails_on_invalid_pattern(small_kosha):
+    with pytest.raises(ValueError):
+        small_kosha.search_regex("gacCa(")
+
+
+def test_search_glob(small_kosha):
+    results = small_kosha.search_glob("*ati")
+    assert [k for k, _ in results] == ["Bavati", "gacCati"]
+
+    results = small_kosha.search_glob("?avati")
+    assert [k for k, _ in results] == ["Bavati"]
+
+    assert small_kosha.search_glob("gacCa") == []
+    assert small_kosha.search_glob("Bava.i") == []
+
+
+def test_forms_of():
+    def tin(lakara, vacana):
+        return Pada.make_tinanta(
+            dhatu=Dhatu(text="gam"),
+            purusha=Purusha.Prathama,
+            lakara=lakara,
+            vacana=vacana,
+            pada_prayoga=PadaPrayoga.Parasmaipada,
+        )
+
+    agacchat = tin(Lakara.Lan, Vacana.Eka)
+    gacchanti = tin(Lakara.Lat, Vacana.Bahu)
+    gacchati = tin(Lakara.Lat, Vacana.Eka)
+    gacchati_sup = Pada.make_subanta(
+        pratipadika=Pratipadika(text="gam"),
+        linga=Linga.Pum,
+        vibhakti=Vibhakti.V7,
+        vacana=Vacana.Eka,
+    )
+    devah = Pada.make_subanta(
+        pratipadika=Pratipadika(text="deva"),
+        linga=Linga.Pum,
+        vibhakti=Vibhakti.V1,
+        vacana=Vacana.Eka,
+    )
+
+    with tempfile.TemporaryDirectory() as tempdir:
+        b = Builder(tempdir)
+        b.insert("agacCat", agacchat)
+        b.insert("devaH", devah)
+        b.insert("gacCanti", gacchanti)
+        b.insert("gacCati", gacchati)
+        b.insert("gacCati", gacchati_sup)
+        b.finish()
+        assert not list(Path(tempdir).glob("*.tmp"))
+        kosha = Kosha(tempdir)
+
+        assert kosha.forms_of("gam") == [
+            ("agacCat", agacchat),
+            ("gacCanti", gacchanti),
+            ("gacCati", gacchati),
+            ("gacCati", gacchati_sup),
+        ]
+        assert kosha.forms_of("gam", PartOfSpeech.Tinanta, lakara=Lakara.Lat) == [
+            ("gacCanti", gacchanti),
+            ("gacCati", gacchati),
+        ]
+        assert kosha.forms_of("gam", pos=PartOfSpeech.Subanta) == [
+            ("gacCati", gacchati_sup)
+        ]
+        assert kosha.forms_of("deva", vibhakti=Vibhakti.V1) == [("devaH", devah)]
+        assert kosha.forms_of("deva", vibhakti=Vibhakti.V2) == []
+        assert kosha.forms_of("ga") == []
+
+        with pytest.raises(ValueError):
+            kosha.forms_of("gam", tense=Lakara.Lat)
+
+
+def test_forms_of_without_index():
+    with tempfile.TemporaryDirectory() as tempdir:
+        b = Builder(tempdir)
+        b.insert("gacCati", Pada.make_avyaya(pratipadika=Pratipadika(text="gacCati")))
+        b.finish()
+        (Path(tempdir) / "lemmas.fst").unlink()
+        kosha = Kosha(tempdir)
+
+    assert "gacCati" in kosha
+    with pytest.raises(OSError, match="no lemma index.*Builder"):
+        kosha.forms_of("gacCati")
+
+
+def test_pickle():
+    with tempfile.TemporaryDirectory() as tempdir:
+        b = Builder(tempdir)
+        b.insert("gacCati", Pada.make_avyaya(pratipadika=Pratipadika(text="gacCati")))
+        b.finish()
+
+        kosha = pickle.loads(pickle.dumps(Kosha(tempdir)))
+        assert type(kosha) is Kosha
+        assert "gacCati" in kosha
+
+
 def test_contains(kosha):
     assert "Bavati" not in kosha
     assert "gacCati" in kosha
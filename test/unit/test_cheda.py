@@ -1,16 +1,36 @@
+import gc
+import json
+import pickle
 import tempfile
+import warnings
 from pathlib import Path
 
 import pytest
 
-from vidyut.cheda import Chedaka
+from vidyut.cheda import (
+    Chedaka,
+    Compound,
+    Constraints,
+    SamasaType,
+    Token,
+    UnknownWordError,
+    evaluate,
+    group_compounds,
+    to_conllu,
+    to_json,
+)
 from vidyut.kosha import PartOfSpeech
+from vidyut.lipi import Scheme
 
 
 from vidyut.kosha import (
     Builder,
+    Kosha,
+    Linga,
     Pada,
     Pratipadika,
+    Vacana,
+    Vibhakti,
 )
 
 
@@ -36,7 +56,8 @@ def create_sandhi_rules(output_path):
     with open(output_path, "w") as f:
         f.write("first,second,result\n")
         f.write("i,a,y a\n")
-        f.write("as,g,o g\n")
+        # Quote one field to check that we parse rules as CSV.
+        f.write('as,g,"o g"\n')
 
 
 def create_model_files(model_dir):
@@ -60,7 +81,8 @@ def chedaka() -> Chedaka:
         create_sandhi_rules(tempdir / "sandhi-rules.csv")
         create_model_files(tempdir / "model")
 
-        return Chedaka(tempdir)
+        # Keep the directory alive, since some methods read data on demand.
+        yield Chedaka(tempdir)
 
 
 def test_init(chedaka):
@@ -88,6 +110,17 @@ def test_init__kosha_invalid():
         c = Chedaka(tempdir)
 
 
+def test_pickle(tmp_path):
+    create_kosha(tmp_path / "kosha")
+    create_sandhi_rules(tmp_path / "sandhi-rules.csv")
+    create_model_files(tmp_path / "model")
+    chedaka = Chedaka(tmp_path)
+
+    copy = pickle.loads(pickle.dumps(chedaka))
+    assert type(copy) is Chedaka
+    assert [t.text for t in copy.run("arjunogacCati")] == ["arjunas", "gacCati"]
+
+
 def test_run__single_word(chedaka):
     tokens = chedaka.run("gacCati")
 
@@ -95,6 +128,8 @@ def test_run__single_word(chedaka):
     gacchati = tokens[0]
     assert gacchati.text == "gacCati"
     assert gacchati.info.pos == PartOfSpeech.Avyaya
+    assert not gacchati.is_unknown
+    assert gacchati.guess is None
 
 
 def test_run__unknown_word(chedaka):
@@ -104,8 +139,557 @@ def test_run__unknown_word(chedaka):
     gacchati = tokens[0]
     assert gacchati.text == "gacCatf"
     assert gacchati.info.pos is None
+    assert gacchati.is_unknown
+
+
+def test_run__unknown_word_guess(chedaka):
+    tokens = chedaka.run("devasya")
+
+    assert len(tokens) == 1
+    devasya = tokens[0]
+    assert devasya.is_unknown
+    assert devasya.guess.stem == "deva"
+    assert devasya.guess.ending == "sya"
+
+
+def test_run__strict(chedaka):
+    assert chedaka.run("gacCati", strict=True)
+
+    with pytest.raises(UnknownWordError) as e:
+        chedaka.run("gacCatf", strict=True)
+    assert e.value.args[1] == [(0, 7, "gacCatf")]
+
+
+def test_run__strict_error_is_value_error(chedaka):
+    with pytest.raises(ValueError):
+        chedaka.run("gacCatf", strict=True)
+
+
+def test_run__offsets(chedaka):
+    text = "arjunogacCati"
+    tokens = chedaka.run(text)
+
+    assert [t.text for t in tokens] == ["arjunas", "gacCati"]
+    assert [(t.start, t.end) for t in tokens] == [(0, 6), (6, 13)]
+    assert [t.surface for t in tokens] == ["arjuno", "gacCati"]
+    for t in tokens:
+        assert text[t.start : t.end] == t.surface
+
+
+def test_run__with_schemes(chedaka):
+    text = "अर्जुनोगच्छति"
+    tokens = chedaka.run(
+        text, input_scheme=Scheme.Devanagari, output_scheme=Scheme.Devanagari
+    )
+
+    assert [t.text for t in tokens] == ["अर्जुनस्", "गच्छति"]
+    assert [t.surface for t in tokens] == ["अर्जुनो", "गच्छति"]
+    for t in tokens:
+        assert text[t.start : t.end] == t.surface
+
+
+def test_run__with_token_constraint(chedaka):
+    text = "arjunogacCati"
+    tokens = chedaka.run(text, constraints=Constraints(tokens=[(0, 13)]))
+
+    assert len(tokens) == 1
+    [token] = tokens
+    assert token.text == "arjunogacCati"
+    assert (token.start, token.end) == (0, 13)
+    assert token.info.pos is None
+
+
+def test_run__with_boundary_constraint(chedaka):
+    text = "gacCatigacCati"
+    tokens = chedaka.run(text, constraints=Constraints(boundaries=[7]))
+
+    assert [t.text for t in tokens] == ["gacCati", "gacCati"]
+    assert [(t.start, t.end) for t in tokens] == [(0, 7), (7, 14)]
+
+
+def test_run__with_boundary_constraint_across_sandhi(chedaka):
+    text = "gacCatyarjunas"
+    tokens = chedaka.run(text, constraints=Constraints(boundaries=[7]))
+
+    assert [t.text for t in tokens] == ["gacCati", "arjunas"]
+    assert not any(t.is_unknown for t in tokens)
+    assert [(t.start, t.end) for t in tokens] == [(0, 7), (7, 14)]
+    assert [t.surface for t in tokens] == ["gacCaty", "arjunas"]
+
+
+@pytest.mark.parametrize(
+    "constraints",
+    [
+        Constraints(tokens=[(0, 20)]),
+        Constraints(tokens=[(3, 3)]),
+        Constraints(tokens=[(0, 5), (4, 8)]),
+        Constraints(tokens=[(0, 5)], boundaries=[3]),
+        Constraints(boundaries=[20]),
+    ],
+)
+def test_run__with_invalid_constraints(chedaka, constraints):
+    with pytest.raises(ValueError):
+        chedaka.run("gacCatigacCati", constraints=constraints)
+
+
+def test_run__with_extra_lexicon():
+    with tempfile.TemporaryDirectory() as tempdir:
+        tempdir: Path = Path(tempdir)
+        create_kosha(tempdir / "kosha")
+        create_sandhi_rules(tempdir / "sandhi-rules.csv")
+        create_model_files(tempdir / "model")
+
+        b = Builder(tempdir / "extra")
+        pada = Pada.make_avyaya(pratipadika=Pratipadika(text="vAtapitta"))
+        b.insert("vAtapitta", pada)
+        b.finish()
+        extra = Kosha(tempdir / "extra")
+
+        chedaka = Chedaka(tempdir)
+        tokens = chedaka.run("vAtapittagacCati")
+        assert "vAtapitta" not in [t.text for t in tokens]
+
+        chedaka.add_lexicon(extra)
+        tokens = chedaka.run("vAtapittagacCati")
+        assert [t.text for t in tokens] == ["vAtapitta", "gacCati"]
+        assert tokens[0].lemma == "vAtapitta"
+        assert [(t.start, t.end) for t in tokens] == [(0, 9), (9, 16)]
+
+        chedaka = Chedaka(tempdir, extra_lexicons=[extra])
+        tokens = chedaka.run("vAtapittagacCati")
+        assert [t.text for t in tokens] == ["vAtapitta", "gacCati"]
+
+        chedaka = pickle.loads(pickle.dumps(chedaka))
+        tokens = chedaka.run("vAtapittagacCati")
+        assert [t.text for t in tokens] == ["vAtapitta", "gacCati"]
+
+
+def test_run__with_extra_lexicon_inside_known_word():
+    with tempfile.TemporaryDirectory() as tempdir:
+        tempdir: Path = Path(tempdir)
+        create_kosha(tempdir / "kosha")
+        create_sandhi_rules(tempdir / "sandhi-rules.csv")
+        create_model_files(tempdir / "model")
+
+        b = Builder(tempdir / "extra")
+        pada = Pada.make_avyaya(pratipadika=Pratipadika(text="gacCa"))
+        b.insert("gacCa", pada)
+        b.finish()
+
+        chedaka = Chedaka(tempdir, extra_lexicons=[Kosha(tempdir / "extra")])
+
+        # `gacCati` is a known word, so the user key `gacCa` must not split it.
+        tokens = chedaka.run("arjunogacCati")
+        assert [t.text for t in tokens] == ["arjunas", "gacCati"]
+        assert not any(t.is_unknown for t in tokens)
+
+
+@pytest.fixture
+def make_chedaka(tmp_path):
+    """Return a function that creates a `Chedaka` with an extra lexicon.
+
+    The function takes a list of `(key, pada)` pairs, sorted by key, for the extra lexicon. It
+    may be called only once per test.
+    """
+    create_kosha(tmp_path / "kosha")
+    create_sandhi_rules(tmp_path / "sandhi-rules.csv")
+    create_model_files(tmp_path / "model")
+
+    def make(extra_entries):
+        b = Builder(tmp_path / "extra")
+        for key, pada in extra_entries:
+            b.insert(key, pada)
+        b.finish()
+        return Chedaka(tmp_path, extra_lexicons=[Kosha(tmp_path / "extra")])
+
+    return make
+
+
+def test_group_compounds(make_chedaka):
+    rama = Pada.make_subanta(
+        pratipadika=Pratipadika(text="rAma"),
+        linga=None,
+        vibhakti=None,
+        vacana=None,
+        is_purvapada=True,
+    )
+    lakshmanau = Pada.make_subanta(
+        pratipadika=Pratipadika(text="lakzmaRa"),
+        linga=Linga.Pum,
+        vibhakti=Vibhakti.V1,
+        vacana=Vacana.Dvi,
+    )
+    chedaka = make_chedaka([("lakzmaRO", lakshmanau), ("rAma", rama)])
+    groups = group_compounds(chedaka.run("rAmalakzmaRO gacCati"))
+
+    assert len(groups) == 2
+    compound, gacchati = groups
+    assert isinstance(compound, Compound)
+    assert [t.text for t in compound.members] == ["rAma", "lakzmaRO"]
+    assert (compound.start, compound.end) == (0, 12)
+    assert compound.surface == "rAmalakzmaRO"
+    assert compound.samasa_type == SamasaType.Dvandva
+    assert isinstance(gacchati, Token)
+
+
+def test_group_compounds__bahuvrihi_prefix(make_chedaka):
+    # `Chedaka` marks every compound member as a subanta, even an indeclinable like `su`, so we
+    # guess the type from the member's lemma.
+    su = Pada.make_subanta(
+        pratipadika=Pratipadika(text="su"),
+        linga=None,
+        vibhakti=None,
+        vacana=None,
+        is_purvapada=True,
+    )
+    dhanena = Pada.make_subanta(
+        pratipadika=Pratipadika(text="Dana"),
+        linga=Linga.Pum,
+        vibhakti=Vibhakti.V3,
+        vacana=Vacana.Eka,
+    )
+    chedaka = make_chedaka([("Danena", dhanena), ("su", su)])
+    [compound] = group_compounds(chedaka.run("suDanena"))
+
+    assert isinstance(compound, Compound)
+    assert [t.text for t in compound.members] == ["su", "Danena"]
+    assert compound.samasa_type == SamasaType.Bahuvrihi
+
+
+def test_group_compounds__avyayibhava_prefix(make_chedaka):
+    # `yathA` is an indeclinable. As above, `Chedaka` gives it as a subanta member.
+    yatha = Pada.make_subanta(
+        pratipadika=Pratipadika(text="yaTA"),
+        linga=None,
+        vibhakti=None,
+        vacana=None,
+        is_purvapada=True,
+    )
+    shakti = Pada.make_avyaya(pratipadika=Pratipadika(text="Sakti"))
+    chedaka = make_chedaka([("Sakti", shakti), ("yaTA", yatha)])
+    [compound] = group_compounds(chedaka.run("yaTASakti"))
+
+    assert isinstance(compound, Compound)
+    assert [t.text for t in compound.members] == ["yaTA", "Sakti"]
+    assert compound.samasa_type == SamasaType.Avyayibhava
 
 
 def test_run__invalid_input(chedaka):
     with pytest.raises(ValueError, match="ASCII") as e:
         tokens = chedaka.run("गच्छति")
+
+
+def test_run_document(chedaka):
+    text = "gacCati | arjunas || 1 ||\n\ngacCati"
+    sentences = chedaka.run_document(text)
+
+    assert [s.text for s in sentences] == ["gacCati", "arjunas", "gacCati"]
+    assert [s.ends_verse for s in sentences] == [False, True, False]
+    assert [s.verse_number for s in sentences] == [None, "1", None]
+
+    for s in sentences:
+        assert text[s.start : s.end] == s.text
+        [token] = s.tokens
+        assert text[token.start : token.end] == token.text
+
+
+def test_run_document__devanagari(chedaka):
+    text = "गच्छति । अर्जुनस् ॥ १ ॥"
+    sentences = chedaka.run_document(text, input_scheme=Scheme.Devanagari)
+
+    assert [s.text for s in sentences] == ["गच्छति", "अर्जुनस्"]
+    assert [[t.text for t in s.tokens] for s in sentences] == [
+        ["gacCati"],
+        ["arjunas"],
+    ]
+    assert sentences[1].verse_number == "१"
+
+
+def test_run_document__bare_verse_number(chedaka):
+    text = "गच्छति । अर्जुनस् ।\n॥ १ ॥\nगच्छति"
+    sentences = chedaka.run_document(text, input_scheme=Scheme.Devanagari)
+
+    assert [s.text for s in sentences] == ["गच्छति", "अर्जुनस्", "गच्छति"]
+    assert [s.ends_verse for s in sentences] == [False, True, False]
+    assert [s.verse_number for s in sentences] == [None, "१", None]
+
+
+def test_iter_file(chedaka):
+    with tempfile.TemporaryDirectory() as tempdir:
+        path = Path(tempdir) / "input.txt"
+        path.write_text("gacCati\n\narjunogacCati\n")
+
+        results = list(chedaka.iter_file(path))
+
+    assert [n for n, _ in results] == [1, 3]
+    assert [t.text for t in results[0][1]] == ["gacCati"]
+    assert [t.text for t in results[1][1]] == ["arjunas", "gacCati"]
+
+
+def test_iter_file__by_verse(chedaka):
+    with tempfile.TemporaryDirectory() as tempdir:
+        path = Path(tempdir) / "input.txt"
+        path.write_text("arjunas |\ngacCati || 1 ||\ngacCati ||\n")
+
+        results = list(chedaka.iter_file(path, by_verse=True))
+
+    assert [n for n, _ in results] == [1, 3]
+    assert [t.text for t in results[0][1]] == ["arjunas", "gacCati"]
+
+
+def test_iter_file__with_encoding(chedaka):
+    with tempfile.TemporaryDirectory() as tempdir:
+        path = Path(tempdir) / "input.txt"
+        path.write_text("गच्छति\nअर्जुनोगच्छति\n", encoding="utf-16")
+
+        results = list(
+            chedaka.iter_file(path, encoding="utf-16", scheme=Scheme.Devanagari)
+        )
+
+    assert [n for n, _ in results] == [1, 2]
+    assert [t.text for t in results[0][1]] == ["गच्छति"]
+    assert [t.text for t in results[1][1]] == ["अर्जुनस्", "गच्छति"]
+
+
+def test_iter_file__with_encoding_closes_file_early(chedaka):
+    with tempfile.TemporaryDirectory() as tempdir:
+        path = Path(tempdir) / "input.txt"
+        path.write_text("गच्छति\nअर्जुनोगच्छति\n", encoding="utf-16")
+
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            it = chedaka.iter_file(path, encoding="utf-16", scheme=Scheme.Devanagari)
+            next(it)
+            del it
+            gc.collect()
+
+    assert not [w for w in caught if issubclass(w.category, ResourceWarning)]
+
+
+def test_iter_file__unknown_encoding(chedaka):
+    with tempfile.TemporaryDirectory() as tempdir:
+        path = Path(tempdir) / "input.txt"
+        path.write_text("gacCati\n")
+
+        with pytest.raises(LookupError):
+            chedaka.iter_file(path, encoding="not-an-encoding")
+
+
+def test_to_conllu(chedaka):
+    conllu = to_conllu(chedaka.run("arjunogacCati"))
+
+    assert conllu.splitlines() == [
+        "1\tarjunas\tarjunas\tADV\t_\t_\t_\t_\t_\tTokenRange=0:6",
+        "2\tgacCati\tgacCati\tADV\t_\t_\t_\t_\t_\tTokenRange=6:13",
+    ]
+
+
+def test_to_json(chedaka):
+    data = json.loads(to_json(chedaka.run("arjunogacCati")))
+
+    assert [t["text"] for t in data] == ["arjunas", "gacCati"]
+    assert data[0]["lemma"] == "arjunas"
+    assert (data[0]["start"], data[0]["end"]) == (0, 6)
+
+
+def test_run_conllu(chedaka):
+    conllu = chedaka.run_conllu("arjunogacCati | gacCati ||")
+
+    blocks = conllu.strip().split("\n\n")
+    assert len(blocks) == 2
+    assert blocks[0].splitlines()[:2] == ["# sent_id = 1", "# text = arjunogacCati"]
+    assert blocks[1].splitlines()[2].startswith("1\tgacCati\tgacCati\t")
+
+
+def test_evaluate(chedaka):
+    gold = (
+        "# sent_id = 1\n"
+        "# text = arjunogacCati\n"
+        "1\tarjunas\tarjunas\tADV\t_\t_\t_\t_\t_\t_\n"
+        "2\tgacCati\tgacCati\tADV\t_\t_\t_\t_\t_\t_\n"
+        "\n"
+        "# sent_id = 2\n"
+        "# text = gacCati\n"
+        "1\tgacCati\tgam\tVERB\t_\t_\t_\t_\t_\t_\n"
+        "\n"
+    )
+    with tempfile.TemporaryDirectory() as tempdir:
+        path = Path(tempdir) / "gold.conllu"
+        path.write_text(gold)
+
+        report = evaluate(chedaka, path)
+
+    assert report.num_sentences == 2
+    assert report.segmentation.f1 == 1.0
+    assert report.lemma.num_correct == 2
+    assert report.lemma.recall == pytest.approx(2 / 3)
+
+    assert len(report.errors) == 1
+    errors = report.errors[0]
+    assert errors.sent_id == "2"
+    assert errors.missing == ["gacCati/gam/_"]
+    assert errors.extra == ["gacCati/gacCati/_"]
+
+
+def test_evaluate__with_final_visarga(chedaka):
+    gold = (
+        "# sent_id = 1\n"
+        "# text = arjunogacCati\n"
+        "1\tarjunaH\tarjunas\tADV\t_\t_\t_\t_\t_\t_\n"
+        "2\tgacCati\tgacCati\tADV\t_\t_\t_\t_\t_\t_\n"
+        "\n"
+    )
+    with tempfile.TemporaryDirectory() as tempdir:
+        path = Path(tempdir) / "gold.conllu"
+        path.write_text(gold)
+
+        report = evaluate(chedaka, path)
+
+    assert report.segmentation.f1 == 1.0
+    assert report.errors == []
+
+
+def test_evaluate__with_failed_sentence(chedaka):
+    gold = (
+        "# sent_id = 1\n"
+        "# text = गच्छति\n"
+        "1\tgacCati\tgam\tVERB\t_\t_\t_\t_\t_\t_\n"
+        "\n"
+        "# sent_id = 2\n"
+        "# text = gacCati\n"
+        "1\tgacCati\tgacCati\tADV\t_\t_\t_\t_\t_\t_\n"
+        "\n"
+    )
+    with tempfile.TemporaryDirectory() as tempdir:
+        path = Path(tempdir) / "gold.conllu"
+        path.write_text(gold)
+
+        report = evaluate(chedaka, path)
+
+    assert report.num_sentences == 2
+    assert report.num_failed == 1
+    assert report.segmentation.num_correct == 1
+    assert report.segmentation.num_gold == 2
+
+    [errors] = report.errors
+    assert errors.sent_id == "1"
+    assert "ASCII" in errors.error
+    assert errors.missing == ["gacCati/gam/_"]
+    assert errors.extra == []
+
+
+def test_lattice(chedaka):
+    lattice = chedaka.lattice("arjunogacCati")
+
+    assert lattice.text == "arjunogacCati"
+    best = [n for n in lattice.nodes if n.in_best_path]
+    assert [n.text for n in best] == ["arjunas", "gacCati"]
+    assert (best[0].start, best[0].end) == (0, 6)
+    assert (best[1].start, best[1].end) == (6, 13)
+
+    edges = {(e.source, e.target) for e in lattice.edges}
+    assert (best[0].id, best[1].id) in edges
+
+
+def test_lattice__to_json(chedaka):
+    lattice = chedaka.lattice("arjunogacCati")
+    data = json.loads(lattice.to_json())
+
+    assert data["text"] == "arjunogacCati"
+    assert len(data["nodes"]) == len(lattice.nodes)
+    assert len(data["edges"]) == len(lattice.edges)
+    node = data["nodes"][0]
+    assert node["info"]["pos"] == "Avyaya"
+
+
+def test_lattice__to_dot(chedaka):
+    dot = chedaka.lattice("arjunogacCati").to_dot()
+
+    assert dot.startswith("digraph lattice {")
+    assert "gacCati" in dot
+    assert "->" in dot
+
+
+def test_sandhi_boundaries(chedaka):
+    boundaries = chedaka.sandhi_boundaries("arjunogacCati")
+
+    assert len(boundaries) == 1
+    b = boundaries[0]
+    assert (b.start, b.end) == (0, 13)
+    assert b.surface == "arjunogacCati"
+    assert (b.first, b.second) == ("arjunas", "gacCati")
+    assert (b.rule.first, b.rule.second, b.rule.result) == ("as", "g", "o g")
+
+
+def test_sandhi_boundaries__juxtaposed(chedaka):
+    boundaries = chedaka.sandhi_boundaries("gacCati gacCati")
+
+    assert len(boundaries) == 1
+    assert boundaries[0].rule is None
+    assert boundaries[0].is_valid
+
+
+def test_run_batch(chedaka):
+    results = chedaka.run_batch(["gacCati", "arjunas"])
+
+    assert len(results) == 2
+    assert [t.text for t in results[0]] == ["gacCati"]
+    assert [t.text for t in results[1]] == ["arjunas"]
+
+
+def test_run_batch__with_num_threads(chedaka):
+    texts = ["gacCati"] * 10
+    results = chedaka.run_batch(texts, num_threads=2)
+
+    assert len(results) == 10
+    assert all([t.text for t in r] == ["gacCati"] for r in results)
+
+
+def test_run_batch__reports_errors_per_item(chedaka):
+    results = chedaka.run_batch(["gacCati", "गच्छति"])
+
+    assert len(results) == 2
+    assert [t.text for t in results[0]] == ["gacCati"]
+    assert isinstance(results[1], ValueError)
+    assert "ASCII" in str(results[1])
+
+
+def test_lemmatize(chedaka):
+    assert chedaka.lemmatize("arjunogacCati") == ["arjunas", "gacCati"]
+    assert chedaka.lemmatize("gacCatf") == [None]
+
+
+def test_lemmatize__with_scheme(chedaka):
+    lemmas = chedaka.lemmatize("अर्जुनोगच्छति", input_scheme=Scheme.Devanagari)
+    assert lemmas == ["arjunas", "gacCati"]
+
+
+def test_lemmatize_batch(chedaka):
+    results = chedaka.lemmatize_batch(["gacCati", "arjunogacCati", "गच्छति"])
+
+    assert results[0] == ["gacCati"]
+    assert results[1] == ["arjunas", "gacCati"]
+    assert isinstance(results[2], ValueError)
+
+
+def test_join(chedaka):
+    assert chedaka.join(["arjunas", "gacCati"]) == "arjuno gacCati"
+    assert chedaka.join(["gacCati", "arjunas"]) == "gacCati arjunas"
+    assert chedaka.join([]) == ""
+
+
+def test_join__with_tokens(chedaka):
+    tokens = chedaka.run("arjunogacCati")
+    assert chedaka.join(tokens) == "arjuno gacCati"
+
+
+def test_join__with_scheme(chedaka):
+    words = ["अर्जुनस्", "गच्छति"]
+    assert chedaka.join(words, scheme=Scheme.Devanagari) == "अर्जुनो गच्छति"
+
+    tokens = chedaka.run("arjunogacCati", output_scheme=Scheme.Devanagari)
+    assert chedaka.join(tokens, scheme=Scheme.Devanagari) == "अर्जुनो गच्छति"
+
+
+def test_join__return_all(chedaka):
+    assert chedaka.join(["arjunas", "gacCati"], return_all=True) == ["arjuno gacCati"]
@@ -0,0 +1,17 @@
+import pytest
+
+from vidyut.lipi import Scheme, transliterate
+
+
+@pytest.mark.parametrize(
+    "text,source,dest,expected",
+    [
+        ("gacCati", Scheme.Slp1, Scheme.Slp1, "gacCati"),
+        ("gacCati", Scheme.Slp1, Scheme.Devanagari, "गच्छति"),
+        ("गच्छति", Scheme.Devanagari, Scheme.Slp1, "gacCati"),
+        ("gacchati", Scheme.Iast, Scheme.Slp1, "gacCati"),
+        ("gacchati", Scheme.HarvardKyoto, Scheme.Slp1, "gacCati"),
+    ],
+)
+def test_transliterate(text, source, dest, expected):
+    assert transliterate(text, source=source, dest=dest) == expected
@@ -9,9 +9,11 @@ This library's main modules are:
 
 - `vidyut.cheda`, which segments Sanskrit expressions
 - `vidyut.kosha`, which compactly stores Sanskrit words
+- `vidyut.lipi`, which transliterates Sanskrit text
 - `vidyut.prakriya`, which generates Sanskrit words
 
 In general, all Vidyut code expects that Sanskrit text uses the SLP1
-transliteration format. For details on how this form is defined, see:
+transliteration format. (`vidyut.lipi` can convert text to and from SLP1.)
+For details on how this form is defined, see:
 https://en.wikipedia.org/wiki/SLP1
 """
@@ -2,5 +2,25 @@
 
 from vidyut.vidyut import cheda as __mod
 
+Boundary = __mod.Boundary
 Chedaka = __mod.Chedaka
+Compound = __mod.Compound
+Constraints = __mod.Constraints
+FileIter = __mod.FileIter
+Guess = __mod.Guess
+Lattice = __mod.Lattice
+LatticeEdge = __mod.LatticeEdge
+LatticeNode = __mod.LatticeNode
+Report = __mod.Report
+SamasaType = __mod.SamasaType
+SandhiRule = __mod.SandhiRule
+Score = __mod.Score
+Sentence = __mod.Sentence
+SentenceErrors = __mod.SentenceErrors
 Token = __mod.Token
+UnknownWordError = __mod.UnknownWordError
+
+evaluate = __mod.evaluate
+group_compounds = __mod.group_compounds
+to_conllu = __mod.to_conllu
+to_json = __mod.to_json
@@ -1,17 +1,226 @@
 from dataclasses import dataclass
+from enum import Enum
 from pathlib import Path
-from typing import List, Dict
+from typing import List, Dict, Optional, Tuple, Union
 
-from vidyut.kosha import Pada
+from vidyut.kosha import Kosha, Pada
+from vidyut.lipi import Scheme
+
+@dataclass
+class Guess:
+    stem: str
+    ending: str
 
 @dataclass
 class Token:
     text: str
     lemma: str
     info: Pada
+    start: int
+    end: int
+    surface: str
+    is_unknown: bool
+    guess: Optional[Guess]
+
+class SamasaType(Enum):
+    Tatpurusha = None
+    Dvandva = None
+    Bahuvrihi = None
+    Avyayibhava = None
+
+@dataclass
+class Compound:
+    members: List[Token]
+    start: int
+    end: int
+    surface: str
+    samasa_type: Optional[SamasaType]
+
+def group_compounds(tokens: List[Token]) -> List[Union[Token, Compound]]:
+    pass
+
+def to_conllu(tokens: List[Token]) -> str:
+    pass
+
+def to_json(tokens: List[Token]) -> str:
+    pass
+
+@dataclass
+class Score:
+    num_correct: int
+    num_predicted: int
+    num_gold: int
+    precision: float
+    recall: float
+    f1: float
+
+@dataclass
+class SentenceErrors:
+    sent_id: Optional[str]
+    text: str
+    missing: List[str]
+    extra: List[str]
+    error: Optional[str]
+
+@dataclass
+class Report:
+    num_sentences: int
+    segmentation: Score
+    lemma: Score
+    tag: Score
+    errors: List[SentenceErrors]
+    num_failed: int
+
+class UnknownWordError(ValueError):
+    pass
+
+class FileIter:
+    def __iter__(self) -> "FileIter":
+        pass
+    def __next__(self) -> Tuple[int, List[Token]]:
+        pass
+
+@dataclass
+class Constraints:
+    tokens: List[Tuple[int, int]]
+    boundaries: List[int]
+
+    def __init__(
+        self,
+        *,
+        tokens: List[Tuple[int, int]] = [],
+        boundaries: List[int] = [],
+    ):
+        pass
+
+@dataclass
+class LatticeNode:
+    id: int
+    text: str
+    start: int
+    end: int
+    info: Pada
+    in_best_path: bool
+
+@dataclass
+class LatticeEdge:
+    source: int
+    target: int
+    is_valid: bool
+
+@dataclass
+class Lattice:
+    text: str
+    nodes: List[LatticeNode]
+    edges: List[LatticeEdge]
+
+    def to_json(self) -> str:
+        pass
+    def to_dot(self) -> str:
+        pass
+
+@dataclass
+class SandhiRule:
+    first: str
+    second: str
+    result: str
+
+@dataclass
+class Boundary:
+    start: int
+    end: int
+    surface: str
+    first: str
+    second: str
+    rule: Optional[SandhiRule]
+    is_valid: bool
+
+@dataclass
+class Sentence:
+    text: str
+    start: int
+    end: int
+    ends_verse: bool
+    verse_number: Optional[str]
+    tokens: List[Token]
 
 class Chedaka:
-    def __init__(self, path: Path | str):
+    def __init__(self, path: Path | str, *, extra_lexicons: List[Kosha] = []):
+        pass
+    def add_lexicon(self, kosha: Kosha):
+        pass
+    def run(
+        self,
+        text: str,
+        *,
+        input_scheme: Scheme = Scheme.Slp1,
+        output_scheme: Scheme = Scheme.Slp1,
+        constraints: Optional[Constraints] = None,
+        strict: bool = False,
+    ) -> List[Token]:
+        pass
+    def run_document(
+        self,
+        text: str,
+        *,
+        input_scheme: Scheme = Scheme.Slp1,
+        output_scheme: Scheme = Scheme.Slp1,
+        strict: bool = False,
+    ) -> List[Sentence]:
+        pass
+    def run_conllu(
+        self,
+        text: str,
+        *,
+        input_scheme: Scheme = Scheme.Slp1,
+        output_scheme: Scheme = Scheme.Slp1,
+    ) -> str:
+        pass
+    def iter_file(
+        self,
+        path: Path | str,
+        *,
+        encoding: str = "utf-8",
+        scheme: Scheme = Scheme.Slp1,
+        by_verse: bool = False,
+    ) -> FileIter:
         pass
-    def run(self, slp1_text: str) -> List[Token]:
+    def lattice(self, slp1_text: str) -> Lattice:
         pass
+    def sandhi_boundaries(self, slp1_text: str) -> List[Boundary]:
+        pass
+    def run_batch(
+        self,
+        texts: List[str],
+        num_threads: Optional[int] = None,
+        *,
+        input_scheme: Scheme = Scheme.Slp1,
+        output_scheme: Scheme = Scheme.Slp1,
+        strict: bool = False,
+    ) -> List[Union[List[Token], Exception]]:
+        pass
+    def join(
+        self,
+        words: List[Union[Token, str]],
+        *,
+        scheme: Scheme = Scheme.Slp1,
+        return_all: bool = False,
+    ) -> Union[str, List[str]]:
+        pass
+    def lemmatize(
+        self, text: str, *, input_scheme: Scheme = Scheme.Slp1
+    ) -> List[Optional[str]]:
+        pass
+    def lemmatize_batch(
+        self,
+        texts: List[str],
+        num_threads: Optional[int] = None,
+        *,
+        input_scheme: Scheme = Scheme.Slp1,
+    ) -> List[Union[List[Optional[str]], Exception]]:
+        pass
+
+def evaluate(
+    chedaka: Chedaka, gold_path: Path | str, *, scheme: Scheme = Scheme.Slp1
+) -> Report:
+    pass
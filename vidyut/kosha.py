@@ -3,6 +3,7 @@
 from vidyut.vidyut import kosha as __mod
 
 Builder = __mod.Builder
+KeyIter = __mod.KeyIter
 Kosha = __mod.Kosha
 
 Dhatu = __mod.Dhatu
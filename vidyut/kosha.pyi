@@ -1,4 +1,5 @@
 from pathlib import Path
+from typing import Iterator, List, Optional, Tuple, Union
 from enum import Enum
 
 class PartOfSpeech(Enum):
@@ -88,7 +89,12 @@ class Pada:
         pass
     @staticmethod
     def make_subanta(
-        *, pratipadika: Pratipadika, linga: Linga, vibhakti: Vibhakti, vacana: Vacana
+        *,
+        pratipadika: Pratipadika,
+        linga: Linga,
+        vibhakti: Vibhakti,
+        vacana: Vacana,
+        is_purvapada: bool = False,
     ) -> Pada:
         pass
     @staticmethod
@@ -103,12 +109,45 @@ class Builder:
     def finish(self):
         pass
 
+class KeyIter:
+    def __iter__(self) -> "KeyIter":
+        pass
+    def __next__(self) -> Union[str, Tuple[str, List[Pada]]]:
+        pass
+
 class Kosha:
     def __init__(self, path: Path | str):
         pass
     def __contains__(self, key: str) -> bool:
         pass
+    def __iter__(self) -> Iterator[str]:
+        pass
+    def __len__(self) -> int:
+        pass
     def contains_prefix(self, key: str) -> bool:
         pass
     def get_all(self, key: str) -> List[Pada]:
         pass
+    def iter_prefix(
+        self, prefix: str, limit: Optional[int] = None
+    ) -> Iterator[Tuple[str, List[Pada]]]:
+        pass
+    def search_fuzzy(
+        self, key: str, max_distance: float
+    ) -> List[Tuple[str, float, List[Pada]]]:
+        pass
+    def search_regex(self, pattern: str) -> List[Tuple[str, List[Pada]]]:
+        pass
+    def search_glob(self, pattern: str) -> List[Tuple[str, List[Pada]]]:
+        pass
+    def forms_of(
+        self,
+        lemma: str,
+        pos: Optional[PartOfSpeech] = None,
+        **filters: Union[Purusha, Lakara, PadaPrayoga, Vacana, Linga, Vibhakti, bool],
+    ) -> List[Tuple[str, Pada]]:
+        pass
+    def keys(self) -> Iterator[str]:
+        pass
+    def items(self) -> Iterator[Tuple[str, List[Pada]]]:
+        pass
@@ -0,0 +1,6 @@
+"""`vidyut.lipi` transliterates Sanskrit text between scripts and schemes."""
+
+from vidyut.vidyut import lipi as __mod
+
+Scheme = __mod.Scheme
+transliterate = __mod.transliterate
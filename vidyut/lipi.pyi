@@ -0,0 +1,11 @@
+from enum import Enum
+
+class Scheme(Enum):
+    Devanagari = None
+    HarvardKyoto = None
+    Iast = None
+    Itrans = None
+    Slp1 = None
+
+def transliterate(text: str, *, source: Scheme, dest: Scheme) -> str:
+    pass